@@ -28,6 +28,195 @@ SOFTWARE.*/
 #include <stdint.h>
 #include <stdlib.h>
 
+/**
+ * f16 type in kernel codes
+ */
+#define GPU_FEATURE_SHADER_F16 (1 << 0)
+
+/**
+ * f64 type in kernel codes
+ */
+#define GPU_FEATURE_SHADER_F64 (1 << 1)
+
+/**
+ * i64 and u64 types in kernel codes
+ */
+#define GPU_FEATURE_SHADER_INT64 (1 << 2)
+
+/**
+ * atomicMin and atomicMax of 64 bit integers in storage buffers
+ */
+#define GPU_FEATURE_SHADER_INT64_ATOMIC_MIN_MAX (1 << 3)
+
+/**
+ * every atomic operation of 64 bit integers
+ */
+#define GPU_FEATURE_SHADER_INT64_ATOMIC_ALL_OPS (1 << 4)
+
+/**
+ * subgroup operations in compute kernel codes
+ */
+#define GPU_FEATURE_SUBGROUP (1 << 5)
+
+/**
+ * subgroupBarrier in kernel codes
+ */
+#define GPU_FEATURE_SUBGROUP_BARRIER (1 << 6)
+
+/**
+ * timestamp queries at the start and end of passes
+ */
+#define GPU_FEATURE_TIMESTAMP_QUERY (1 << 7)
+
+/**
+ * timestamp queries inside of command encoders
+ */
+#define GPU_FEATURE_TIMESTAMP_QUERY_INSIDE_ENCODERS (1 << 8)
+
+/**
+ * timestamp queries inside of compute passes
+ */
+#define GPU_FEATURE_TIMESTAMP_QUERY_INSIDE_PASSES (1 << 9)
+
+/**
+ * pipeline statistics queries
+ */
+#define GPU_FEATURE_PIPELINE_STATISTICS_QUERY (1 << 10)
+
+/**
+ * var<push_constant> in kernel codes
+ */
+#define GPU_FEATURE_PUSH_CONSTANTS (1 << 11)
+
+/**
+ * buffers which can be mapped and used by kernel codes at the same
+ * time , it is fast on integrated gpus but slow on discrete ones
+ */
+#define GPU_FEATURE_MAPPABLE_PRIMARY_BUFFERS (1 << 12)
+
+/**
+ * arrays of buffer bindings
+ */
+#define GPU_FEATURE_BUFFER_BINDING_ARRAY (1 << 13)
+
+/**
+ * arrays of storage buffer and storage texture bindings
+ */
+#define GPU_FEATURE_STORAGE_RESOURCE_BINDING_ARRAY (1 << 14)
+
+/**
+ * arrays of texture bindings
+ */
+#define GPU_FEATURE_TEXTURE_BINDING_ARRAY (1 << 15)
+
+/**
+ * texture formats have the usages which the adapter supports
+ * instead of the ones which WebGPU guarantees
+ */
+#define GPU_FEATURE_TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES (1 << 16)
+
+/**
+ * filtering samplers on R32Float , Rg32Float and Rgba32Float textures
+ */
+#define GPU_FEATURE_FLOAT32_FILTERABLE (1 << 17)
+
+/**
+ * 16 bit normalized texture formats
+ */
+#define GPU_FEATURE_TEXTURE_FORMAT_16BIT_NORM (1 << 18)
+
+/**
+ * kernel codes can run on the device , it is false on WebGL
+ */
+#define GPU_DOWNLEVEL_COMPUTE_SHADERS (1 << 0)
+
+/**
+ * indirect dispatches are supported
+ */
+#define GPU_DOWNLEVEL_INDIRECT_EXECUTION (1 << 1)
+
+/**
+ * buffer bindings can have sizes which are not multiple of 16
+ */
+#define GPU_DOWNLEVEL_BUFFER_BINDINGS_NOT_16_BYTE_ALIGNED (1 << 2)
+
+/**
+ * textures which are not power of two sized can have mipmaps
+ */
+#define GPU_DOWNLEVEL_NON_POWER_OF_TWO_MIPMAPPED_TEXTURES (1 << 3)
+
+/**
+ * cube array textures are supported
+ */
+#define GPU_DOWNLEVEL_CUBE_ARRAY_TEXTURES (1 << 4)
+
+/**
+ * comparison samplers are supported
+ */
+#define GPU_DOWNLEVEL_COMPARISON_SAMPLERS (1 << 5)
+
+/**
+ * anisotropic filtering of samplers is supported
+ */
+#define GPU_DOWNLEVEL_ANISOTROPIC_FILTERING (1 << 6)
+
+/**
+ * depth textures can be copied to and from buffers
+ */
+#define GPU_DOWNLEVEL_DEPTH_TEXTURE_AND_BUFFER_COPIES (1 << 7)
+
+/**
+ * every texture format usage which WebGPU guarantees is supported
+ */
+#define GPU_DOWNLEVEL_WEBGPU_TEXTURE_FORMAT_SUPPORT (1 << 8)
+
+/**
+ * resolving queries does not block the host
+ */
+#define GPU_DOWNLEVEL_NONBLOCKING_QUERY_RESOLVE (1 << 9)
+
+/**
+ * since v7.0.0 what samplers return for coordinates
+ * out of 0.0 to 1.0
+ */
+typedef enum GPUAddressMode {
+  ClampToEdge = 0,
+  Repeat = 1,
+  MirrorRepeat = 2,
+} GPUAddressMode;
+
+/**
+ * since v7.0.0 this enum tells to API which kind
+ * of variable the DataBinder is bound to in kernel code
+ */
+typedef enum GPUBindingKind {
+  /**
+   * var<storage> , data is used based on access field
+   */
+  Storage = 0,
+  /**
+   * var<uniform> for small parameters , data is uploaded
+   * into uniform buffer and never read back , access and
+   * read_back_mode fields are ignored
+   */
+  Uniform = 1,
+  /**
+   * texture_storage_2d or texture_storage_3d , texture field
+   * describes data and it is used based on access field
+   * (read_write storage textures are not supported by all gpu devices)
+   */
+  StorageTexture = 2,
+  /**
+   * texture_2d or texture_3d which kernel code can sample , texture
+   * field describes data , it is uploaded and never read back
+   */
+  SampledTexture = 3,
+  /**
+   * sampler , sampler field describes it and data field is ignored
+   */
+  Sampler = 4,
+} GPUBindingKind;
+
 /**
  * computing backends of the api
  */
@@ -70,6 +259,88 @@ typedef enum GPUComputingBackend {
   lowest_support = 8,
 } GPUComputingBackend;
 
+/**
+ * since v7.0.0 every exported function of the api returns
+ * this status instead of panicking , when it is not Success
+ * you can get the description of the problem with emcompute_last_error
+ */
+typedef enum GPUComputingStatus {
+  /**
+   * operation finished without any problem
+   */
+  Success = 0,
+  /**
+   * one of the passed arguments is NULL or has invalid value
+   */
+  InvalidArgument = 1,
+  /**
+   * passed EMContext , EMKernel , buffer_index or other handle
+   * does not point to a living resource
+   */
+  InvalidHandle = 2,
+  /**
+   * no gpu device matched the passed GPUComputingConfig
+   */
+  AdapterNotFound = 3,
+  /**
+   * gpu device exists but it could not provide the requested
+   * limits or features
+   */
+  DeviceRequestFailed = 4,
+  /**
+   * kernel code could not be parsed , validated or compiled
+   */
+  ShaderCompileError = 5,
+  /**
+   * gpu side rejected the task , for example because of
+   * mismatched bindings
+   */
+  ValidationError = 6,
+  /**
+   * reading the results back from gpu side failed
+   */
+  MapFailed = 7,
+  /**
+   * gpu side could not allocate the needed memory
+   */
+  OutOfMemory = 8,
+  /**
+   * unexpected problem inside of the api , please report it
+   */
+  InternalError = 9,
+  /**
+   * gpu device does not support the feature which
+   * is needed for the operation
+   */
+  UnsupportedFeature = 10,
+  /**
+   * computing task of EMJob is not finished yet , it is
+   * not an error and emcompute_last_error is not changed
+   */
+  JobPending = 11,
+} GPUComputingStatus;
+
+/**
+ * since v7.0.0 this enum tells to API how your kernel
+ * code uses data of DataBinder , so copies which are not
+ * needed can be skipped
+ */
+typedef enum GPUDataAccess {
+  /**
+   * data is uploaded to gpu side and read back after computing
+   */
+  ReadWrite = 0,
+  /**
+   * data is only input , it is uploaded but never read back
+   */
+  ReadOnly = 1,
+  /**
+   * data is only output , it is not uploaded , gpu side buffer
+   * is zero initialized and read back after computing
+   */
+  WriteOnly = 2,
+} GPUDataAccess;
+
 /**
  * Computing devices types
  */
@@ -81,6 +352,18 @@ typedef enum GPUDeviceType {
   Cpu = 4,
 } GPUDeviceType;
 
+/**
+ * since v7.0.0 filtering of samplers
+ */
+typedef enum GPUFilterMode {
+  Nearest = 0,
+  /**
+   * needs texture format which can be filtered
+   * (like Rgba8Unorm or Rgba16Float)
+   */
+  Linear = 1,
+} GPUFilterMode;
+
 /**
  * this settings used to tell gpu pre information about
  * our work
@@ -123,6 +406,57 @@ typedef enum GPUPowerSettings {
   HighPerformance = 2,
 } GPUPowerSettings;
 
+/**
+ * since v7.0.0 this enum tells to API where
+ * results of the DataBinder must be written
+ */
+typedef enum GPUReadBackMode {
+  /**
+   * results are copied to the memory which data field
+   * points to , memory stays owned by you
+   */
+  InPlace = 0,
+  /**
+   * API allocates new memory for results and writes its
+   * address to data field , your old memory is not touched .
+   * the new memory must be freed with emcompute_free_bytes
+   */
+  NewAllocation = 1,
+} GPUReadBackMode;
+
+/**
+ * since v7.0.0 scalar type of elements of reflected bindings
+ */
+typedef enum GPUScalarKind {
+  /**
+   * structs , samplers and types which have no single scalar type
+   */
+  NoScalar = 0,
+  Sint = 1,
+  Uint = 2,
+  Float = 3,
+  Bool = 4,
+} GPUScalarKind;
+
+/**
+ * since v7.0.0 collections of shader features which the
+ * device supports , named after shader models of D3D
+ */
+typedef enum GPUShaderModel {
+  /**
+   * very limited shaders
+   */
+  Sm2 = 0,
+  /**
+   * no storage textures
+   */
+  Sm4 = 1,
+  /**
+   * everything which WebGPU needs
+   */
+  Sm5 = 2,
+} GPUShaderModel;
+
 /**
  * this enum affects speed of the api
  * by setting how much gpu resources
@@ -149,8 +483,163 @@ typedef enum GPUSpeedSettings {
    * low_speed
    */
   custom_speed = 3,
+  /**
+   * since v7.0.0 gpu_speed_custom of GPUCustomSettings is used but
+   * its zero fields are taken from default_speed limits and every
+   * limit is clamped to what the gpu device supports , so partially
+   * filled GPUSpeedCustom works on every device .
+   * emcompute_context_limits returns the limits which were used
+   */
+  clamped_custom_speed = 4,
 } GPUSpeedSettings;
 
+/**
+ * since v7.0.0 dimension of textures of DataBinder
+ */
+typedef enum GPUTextureDimension {
+  /**
+   * texture_2d and texture_storage_2d , depth must be 1
+   */
+  Texture2D = 0,
+  /**
+   * texture_3d and texture_storage_3d
+   */
+  Texture3D = 1,
+} GPUTextureDimension;
+
+/**
+ * since v7.0.0 texel formats which textures of DataBinder
+ * can have , storage textures only support the formats
+ * which wgsl texture_storage_* types accept
+ */
+typedef enum GPUTextureFormat {
+  /**
+   * 4 x 8 bit unsigned normalized , texel is 4 bytes
+   */
+  Rgba8Unorm = 0,
+  /**
+   * 4 x 8 bit signed normalized , texel is 4 bytes
+   */
+  Rgba8Snorm = 1,
+  /**
+   * 4 x 8 bit unsigned integer , texel is 4 bytes
+   */
+  Rgba8Uint = 2,
+  /**
+   * 4 x 8 bit signed integer , texel is 4 bytes
+   */
+  Rgba8Sint = 3,
+  /**
+   * 4 x 16 bit unsigned integer , texel is 8 bytes
+   */
+  Rgba16Uint = 4,
+  /**
+   * 4 x 16 bit signed integer , texel is 8 bytes
+   */
+  Rgba16Sint = 5,
+  /**
+   * 4 x 16 bit float , texel is 8 bytes
+   */
+  Rgba16Float = 6,
+  /**
+   * 32 bit unsigned integer , texel is 4 bytes
+   */
+  R32Uint = 7,
+  /**
+   * 32 bit signed integer , texel is 4 bytes
+   */
+  R32Sint = 8,
+  /**
+   * 32 bit float , texel is 4 bytes
+   */
+  R32Float = 9,
+  /**
+   * 2 x 32 bit unsigned integer , texel is 8 bytes
+   */
+  Rg32Uint = 10,
+  /**
+   * 2 x 32 bit signed integer , texel is 8 bytes
+   */
+  Rg32Sint = 11,
+  /**
+   * 2 x 32 bit float , texel is 8 bytes
+   */
+  Rg32Float = 12,
+  /**
+   * 4 x 32 bit unsigned integer , texel is 16 bytes
+   */
+  Rgba32Uint = 13,
+  /**
+   * 4 x 32 bit signed integer , texel is 16 bytes
+   */
+  Rgba32Sint = 14,
+  /**
+   * 4 x 32 bit float , texel is 16 bytes
+   */
+  Rgba32Float = 15,
+  /**
+   * 8 bit unsigned normalized , texel is 1 byte ,
+   * only for sampled textures
+   */
+  R8Unorm = 16,
+} GPUTextureFormat;
+
+/**
+ * opaque handle of recorded dispatches which
+ * emcompute_command_list_create allocates , it must be
+ * destroyed with emcompute_command_list_destroy
+ */
+typedef struct EMCommandList EMCommandList;
+
+/**
+ * opaque handle of gpu resources which create_computing_gpu_resources
+ * allocates , it must be destroyed with emcompute_context_destroy
+ */
+typedef struct EMContext EMContext;
+
+/**
+ * opaque handle of computing task which compute_async submitted ,
+ * it must be destroyed with emcompute_job_destroy
+ */
+typedef struct EMJob EMJob;
+
+/**
+ * opaque handle of registered kernel code , it must be
+ * destroyed with emcompute_kernel_destroy
+ */
+typedef struct EMKernel EMKernel;
+
+/**
+ * opaque handle of devices which emcompute_multi_device_create
+ * allocates , it must be destroyed with emcompute_multi_device_destroy
+ */
+typedef struct EMMultiDevice EMMultiDevice;
+
+/**
+ * since v7.0.0 describes gpu devices which create_computing_gpu_resources
+ * can pick , fields are compared with GPUDeviceInfo of the devices and
+ * zero fields and NULL name match every device
+ */
+typedef struct GPUDeviceSelector {
+  /**
+   * when it is true only devices of device_type match
+   */
+  bool match_device_type;
+  enum GPUDeviceType device_type;
+  /**
+   * vendor ID of the device , 0 matches every vendor
+   */
+  uint32_t vendor;
+  /**
+   * device id of the device , 0 matches every device
+   */
+  uint32_t device;
+  /**
+   * part of the device name , case is ignored
+   */
+  const char *name;
+} GPUDeviceSelector;
+
 /**
  * as config field you have to provide GPUComputingConfig which
  * represent settings which you wanted
@@ -183,9 +672,45 @@ typedef struct GPUComputingConfig {
    * free_gpu_devices_infos function must be used from C side of the program to deallocate
    * recived gpu infos , in Rust RAII will take care of it
    * ```
-   * if it sets to negative value , API will automatically choose the gpu device
+   * if it sets to negative value , API will automatically choose the gpu device .
+   * since v7.0.0 it is ignored when device_selectors_len is not 0 , order of
+   * the list can change with driver updates so device_selectors are preferred
    */
   int64_t gpu_index_in_backend_group;
+  /**
+   * since v7.0.0 if it is set to true and the gpu device supports
+   * push constants , they are enabled with max_push_constant_size
+   * of the device (for custom_speed max_push_constant_size of GPUSpeedCustom
+   * is used) , so kernel codes can use var<push_constant> and
+   * compute_with_push_constants function
+   */
+  bool push_constants;
+  /**
+   * since v7.0.0 GPU_FEATURE bits of the features which must be
+   * enabled , when the gpu device misses one of them UnsupportedFeature
+   * is returned and other bits than GPU_FEATURE bits are InvalidArgument .
+   * emcompute_context_features returns what is enabled
+   */
+  uint64_t required_features;
+  /**
+   * since v7.0.0 GPU_FEATURE bits of the features which are enabled
+   * only when the gpu device supports them , unknown bits are ignored
+   */
+  uint64_t optional_features;
+  /**
+   * since v7.0.0 array of GPUDeviceSelector which picks the gpu device
+   * by its type , vendor , device id or name . selectors are tried in
+   * order and the first one which matches a device of the backend is
+   * used , between matching devices discrete gpus are picked first .
+   * when none of them matches AdapterNotFound is returned . only
+   * create_computing_gpu_resources reads it , Rust side passes
+   * DeviceSelectors to Context::with_device_selectors instead
+   */
+  const struct GPUDeviceSelector *device_selectors;
+  /**
+   * len of device_selectors , 0 means device_selectors is not used
+   */
+  uintptr_t device_selectors_len;
 } GPUComputingConfig;
 
 /**
@@ -258,6 +783,26 @@ typedef struct GPUCustomSettings {
   struct GPUMemoryCustom gpu_memory_custom;
 } GPUCustomSettings;
 
+/**
+ * since v7.0.0 value of one override declaration of wgsl
+ * kernel code , name is its name or its @id as text for
+ * declarations which have @id
+ */
+typedef struct GPUOverrideConstant {
+  const char *name;
+  double value;
+} GPUOverrideConstant;
+
+/**
+ * since v7.0.0 one #define of GPU side code , name is the
+ * macro name and value is its replacement which can be NULL
+ * for macros which are only checked with #ifdef
+ */
+typedef struct GPUShaderDefine {
+  const char *name;
+  const char *value;
+} GPUShaderDefine;
+
 /**
  * CKernel which will represent your GPU task
  * like how Manifest.xml does in an android
@@ -278,20 +823,46 @@ typedef struct CKernel {
   uint32_t z;
   /**
    * since v4.0.0 instead of directly passing
-   * kernel code , you have to pass return
-   * value of register_computing_kernel_code
+   * kernel code , you have to pass handle
+   * which register_computing_kernel_code writes
    * to this field
    */
-  uintptr_t kernel_code_index;
+  struct EMKernel *kernel_code;
   /**
    * since v4.0.0 instead of directly passing
    * configs of your computing task
-   * you have to create_computing_gpu_resources
-   * return value to this field
+   * you have to pass handle which
+   * create_computing_gpu_resources writes to this field
    */
-  uintptr_t config_index;
+  struct EMContext *context;
 } CKernel;
 
+/**
+ * since v7.0.0 shape of textures of DataBinder , host data
+ * of the texture must be tightly packed texels , row by row
+ * and slice by slice , so its len must be
+ * width * height * depth * texel size of format .
+ * padding of rows which gpu side needs is handled by the API
+ */
+typedef struct GPUTextureInfo {
+  enum GPUTextureFormat format;
+  enum GPUTextureDimension dimension;
+  uint32_t width;
+  uint32_t height;
+  /**
+   * number of slices of 3D textures , it must be 1 for 2D textures
+   */
+  uint32_t depth;
+} GPUTextureInfo;
+
+/**
+ * since v7.0.0 settings of samplers of DataBinder
+ */
+typedef struct GPUSamplerInfo {
+  enum GPUFilterMode filter;
+  enum GPUAddressMode address_mode;
+} GPUSamplerInfo;
+
 /**
  * this struct is for passing
  * data based on its bind index
@@ -316,6 +887,47 @@ typedef struct DataBinder {
    * be set based on CKernel code you provided
    */
   uint8_t **data;
+  /**
+   * since v7.0.0 if it is set to non negative value , the computing
+   * buffer with this index (returned by create_computing_buffer) is
+   * bound instead of data , data and data_len are ignored and results
+   * stay on gpu side , use read_computing_buffer to get them .
+   * if it sets to negative value , data field is used
+   */
+  int64_t buffer_index;
+  /**
+   * since v7.0.0 access mode of data field , it is ignored
+   * when buffer_index is used
+   */
+  enum GPUDataAccess access;
+  /**
+   * since v7.0.0 results are copied to the memory which data
+   * points to by default (InPlace) , it is ignored when
+   * buffer_index is used
+   */
+  enum GPUReadBackMode read_back_mode;
+  /**
+   * since v7.0.0 kind of the variable in kernel code , it is
+   * ignored when buffer_index is used because computing buffers
+   * can be bound to both var<storage> and var<uniform>
+   */
+  enum GPUBindingKind kind;
+  /**
+   * since v7.0.0 format and size of data when kind is
+   * StorageTexture or SampledTexture , data_len must be
+   * width * height * depth * texel size of the format
+   */
+  struct GPUTextureInfo texture;
+  /**
+   * since v7.0.0 settings of the sampler when kind is Sampler
+   */
+  struct GPUSamplerInfo sampler;
+  /**
+   * since v7.0.0 it is only used by emcompute_multi_device_compute ,
+   * when it is true data is divided between the devices like the
+   * range , otherwise every device gets the whole data
+   */
+  bool split;
 } DataBinder;
 
 /**
@@ -341,6 +953,139 @@ typedef struct GroupOfBinders {
   uintptr_t datas_len;
 } GroupOfBinders;
 
+/**
+ * since v7.0.0 one problem which was found in kernel code ,
+ * line and column start from 1 and they are 0 when the problem
+ * has no location , snippet is the lines of the code which the
+ * problem points to
+ */
+typedef struct GPUCompileDiagnostic {
+  const char *message;
+  uint32_t line;
+  uint32_t column;
+  const char *snippet;
+} GPUCompileDiagnostic;
+
+/**
+ * since v7.0.0 one @group @binding variable which entry point
+ * of the kernel code uses
+ */
+typedef struct GPUBindingInfo {
+  /**
+   * @group index of the variable
+   */
+  uint32_t group;
+  /**
+   * @binding index of the variable
+   */
+  uint32_t bind;
+  /**
+   * address space of the variable , which is the kind
+   * field that DataBinder of it needs
+   */
+  enum GPUBindingKind kind;
+  /**
+   * access mode of the variable , var<uniform> , sampled
+   * textures and samplers are ReadOnly
+   */
+  enum GPUDataAccess access;
+  /**
+   * scalar type of one element , for textures it is
+   * the type which textureLoad and textureSample return
+   */
+  enum GPUScalarKind element_kind;
+  /**
+   * bytes of one scalar of the element
+   */
+  uint32_t element_width;
+  /**
+   * scalars of one element , 1 for scalars , 2 to 4 for vectors ,
+   * columns * rows for matrices and 0 for structs
+   */
+  uint32_t element_components;
+  /**
+   * bytes between elements of arrays , it is 0 when the variable
+   * is not an array . structs which end with runtime sized array
+   * are reflected as that array
+   */
+  uint32_t stride;
+  /**
+   * number of elements of fixed size arrays , 0 for
+   * runtime sized arrays and variables which are not arrays
+   */
+  uint32_t array_len;
+  /**
+   * minimum number of bytes which bound data must have , runtime
+   * sized arrays are counted with one element . 0 for textures and samplers
+   */
+  uint64_t min_size;
+  /**
+   * format of storage textures , kernel codes which declare
+   * formats that GPUTextureFormat does not have can not be registered
+   */
+  enum GPUTextureFormat texture_format;
+  /**
+   * dimension of textures
+   */
+  enum GPUTextureDimension texture_dimension;
+} GPUBindingInfo;
+
+/**
+ * since v7.0.0 what emcompute_kernel_reflect writes , it must
+ * be freed with emcompute_free_kernel_reflection after usage
+ */
+typedef struct GPUKernelReflection {
+  /**
+   * pointer to array of every @group @binding which entry
+   * point uses , sorted by group and bind
+   */
+  struct GPUBindingInfo *bindings;
+  /**
+   * len of bindings array
+   */
+  uintptr_t bindings_len;
+  /**
+   * @workgroup_size of the entry point in x dimension
+   */
+  uint32_t workgroup_size_x;
+  /**
+   * @workgroup_size of the entry point in y dimension
+   */
+  uint32_t workgroup_size_y;
+  /**
+   * @workgroup_size of the entry point in z dimension
+   */
+  uint32_t workgroup_size_z;
+  /**
+   * size of var<push_constant> in bytes , 0 when it is not used
+   */
+  uint32_t push_constant_size;
+} GPUKernelReflection;
+
+/**
+ * since v7.0.0 function which compute_async calls from another
+ * thread when the computing task is finished , status is what
+ * emcompute_job_poll would return and user_data is the pointer
+ * which is passed to compute_async
+ */
+typedef void (*GPUJobCallback)(enum GPUComputingStatus status, void *user_data);
+
+/**
+ * since v7.0.0 what the device supports of the features which
+ * WebGPU guarantees , older backends like OpenGL ES may miss some
+ */
+typedef struct GPUDownlevelCapabilities {
+  /**
+   * GPU_DOWNLEVEL bits of the supported capabilities
+   */
+  uint32_t flags;
+  enum GPUShaderModel shader_model;
+  /**
+   * true when the device supports everything which WebGPU needs
+   */
+  bool webgpu_compliant;
+} GPUDownlevelCapabilities;
+
 /**
  * this struct is used for storing information about
  * each device
@@ -374,6 +1119,20 @@ typedef struct GPUDeviceInfo {
    * corresponding GPUComputingBackend
    */
   enum GPUComputingBackend backend;
+  /**
+   * since v7.0.0 the best limits which the device supports , they
+   * can be used as gpu_speed_custom of custom_speed settings
+   */
+  struct GPUSpeedCustom limits;
+  /**
+   * since v7.0.0 GPU_FEATURE bits of the features which
+   * the device supports
+   */
+  uint64_t features;
+  /**
+   * since v7.0.0 what the device supports of WebGPU
+   */
+  struct GPUDownlevelCapabilities downlevel;
 } GPUDeviceInfo;
 
 /**
@@ -393,40 +1152,175 @@ typedef struct GPUDevices {
 
 /**
  * since v4.0.0 you must create_computing_gpu_resources
- * it will return gpu_res_descriptor as uintptr_t (usize)
- * and you have to pass it as config_index value to
- * CKernel variable
+ * it will write EMContext handle to context and you
+ * have to pass it as context value to CKernel variable .
+ * since v7.0.0 it returns GPUComputingStatus instead of panicking
+ * and the handle must be destroyed by emcompute_context_destroy
+ */
+enum GPUComputingStatus create_computing_gpu_resources(struct GPUComputingConfig config,
+                                                       struct GPUCustomSettings customize,
+                                                       struct EMContext **context);
+
+/**
+ * since v7.0.0 frees gpu resources of context , its computing
+ * buffers , kernel codes which are registered on it , its jobs ,
+ * command lists and multi devices which it is added to .
+ * the handle and its kernel handles can not be used anymore
+ */
+enum GPUComputingStatus emcompute_context_destroy(struct EMContext *context);
+
+/**
+ * since v7.0.0 writes GPU_FEATURE bits of the features which are
+ * enabled on the gpu device of context to features , they are the
+ * required_features of its GPUComputingConfig and the optional_features
+ * which the device supports
  */
-uintptr_t create_computing_gpu_resources(struct GPUComputingConfig config,
-                                         struct GPUCustomSettings customize);
+enum GPUComputingStatus emcompute_context_features(struct EMContext *context, uint64_t *features);
+
+/**
+ * since v7.0.0 writes the limits which gpu device of context is
+ * created with to limits , for clamped_custom_speed they show
+ * which values were actually used
+ */
+enum GPUComputingStatus emcompute_context_limits(struct EMContext *context,
+                                                 struct GPUSpeedCustom *limits);
 
 /**
  * since v4.0.0 your kernel code must be registered before
- * you want to use it . gpu_res_index is gpu resource descriptor
+ * you want to use it . context is gpu resource handle
  * which you get from create_computing_gpu_resources .
+ * since v7.0.0 handle of the registered kernel code is written to
+ * kernel_code and GPUComputingStatus is returned . when the code can
+ * not be compiled ShaderCompileError is returned and
+ * emcompute_last_compile_diagnostics shows where the problems are
+ */
+enum GPUComputingStatus register_computing_kernel_code(struct EMContext *context,
+                                                       const char *code,
+                                                       const char *entry_point,
+                                                       struct EMKernel **kernel_code);
+
+/**
+ * since v7.0.0 works like register_computing_kernel_code and sets
+ * values of override declarations of the code when its pipeline is
+ * created , so tile sizes and other constants can be specialised per
+ * device without changing the code . constants is array of
+ * constants_len GPUOverrideConstant , it can be NULL when constants_len
+ * is 0 . values are converted to the type of their declaration and
+ * declarations which have no default value must be set .
+ * @workgroup_size can not use overrides yet , use
+ * emcompute_register_kernel_preprocessed for specialising it
+ */
+enum GPUComputingStatus emcompute_register_kernel_with_constants(struct EMContext *context,
+                                                                 const char *code,
+                                                                 const char *entry_point,
+                                                                 const struct GPUOverrideConstant *constants,
+                                                                 uintptr_t constants_len,
+                                                                 struct EMKernel **kernel_code);
+
+/**
+ * since v7.0.0 registers SPIR-V binary module with spirv_len bytes
+ * as kernel code , so compute shaders of other toolchains can be used
+ * without porting them to wgsl . entry_point is the name of its
+ * OpEntryPoint with GLCompute execution model . the module is
+ * translated by naga , so it works on every backend
+ */
+enum GPUComputingStatus emcompute_register_kernel_spirv(struct EMContext *context,
+                                                        const uint8_t *spirv,
+                                                        uintptr_t spirv_len,
+                                                        const char *entry_point,
+                                                        struct EMKernel **kernel_code);
+
+/**
+ * since v7.0.0 registers GLSL compute shader as kernel code , defines
+ * is array of defines_len GPUShaderDefine which are set before the code
+ * like #define lines , it can be NULL when defines_len is 0 . entry
+ * point of GLSL shaders is always main function . the code is
+ * translated by naga , so it works on every backend
+ */
+enum GPUComputingStatus emcompute_register_kernel_glsl(struct EMContext *context,
+                                                       const char *code,
+                                                       const struct GPUShaderDefine *defines,
+                                                       uintptr_t defines_len,
+                                                       struct EMKernel **kernel_code);
+
+/**
+ * since v7.0.0 stores code as source snippet of context with name ,
+ * so kernel codes which are registered with
+ * emcompute_register_kernel_preprocessed can paste it with
+ * #include "name" . snippet with the same name is replaced
  */
-uintptr_t register_computing_kernel_code(uintptr_t gpu_res_index,
-                                         const char *code,
-                                         const char *entry_point);
+enum GPUComputingStatus emcompute_register_source_snippet(struct EMContext *context,
+                                                          const char *name,
+                                                          const char *code);
+
+/**
+ * since v7.0.0 works like register_computing_kernel_code but code is
+ * preprocessed first , so variants of one kernel code can be registered
+ * without pasting strings together . lines of code which start with #
+ * are directives : #define NAME value , #undef NAME , #ifdef NAME ,
+ * #ifndef NAME , #else , #endif and #include "name" which pastes the
+ * snippet of emcompute_register_source_snippet . defined names are
+ * replaced with their values in the rest of the code .
+ * defines is array of defines_len GPUShaderDefine which are set before
+ * the code , it can be NULL when defines_len is 0 . problems of the
+ * directives are returned as ShaderCompileError with diagnostics .
+ * lines , columns and snippets of the diagnostics of the kernel code and
+ * emcompute_last_error point to code or the snippet which the problem is in
+ */
+enum GPUComputingStatus emcompute_register_kernel_preprocessed(struct EMContext *context,
+                                                               const char *code,
+                                                               const char *entry_point,
+                                                               const struct GPUShaderDefine *defines,
+                                                               uintptr_t defines_len,
+                                                               struct EMKernel **kernel_code);
+
+/**
+ * since v7.0.0 frees compute pipeline of the kernel code , the handle
+ * can not be used anymore but other kernel codes of its context keep
+ * working . memory of the handle can be reused by next registered
+ * kernel codes , but the old handle stays invalid
+ */
+enum GPUComputingStatus emcompute_kernel_destroy(struct EMKernel *kernel_code);
 
 /**
  * when your work fully finished with kernel codes and you
  * wont need to use them anymore , you can use this
- * function to cleanup all the mess which they created from memory
+ * function to cleanup all the mess which they created from memory .
+ * since v7.0.0 it destroys every kernel code registered on context ,
+ * for retiring only one of them use emcompute_kernel_destroy
  */
-void free_compute_kernel_codes(uintptr_t gpu_res_index);
+enum GPUComputingStatus free_compute_kernel_codes(struct EMContext *context);
 
 /**
  * because setting CKernel config can be annoying if you just
  * want to do simple task , this function provides general
  * config which will meet most of your needs . since v4.0.0
  * this function calls create_computing_gpu_resources automatically
- * and assign its return value to config_index of your CKernel variable .
+ * and assign its handle to context field of your CKernel variable .
  * only use this function once in your programs , instead of using this
- * many times and causing memory leaks (well all that mem can be freed by free_compute_cache function)
- * use config_index field of CKernel variable
+ * many times and causing memory leaks (well all that mem can be freed by
+ * emcompute_context_destroy or free_compute_cache function)
+ * use context field of CKernel variable .
+ * since v7.0.0 it returns GPUComputingStatus , the handle is only
+ * available through context field of your CKernel variable
+ */
+enum GPUComputingStatus set_kernel_default_config(struct CKernel *kernel);
+
+/**
+ * since v7.0.0 instead of computing x , y and z fields of kernel by hand ,
+ * this function reads @workgroup_size of the registered kernel code and sets
+ * them so size_x * size_y * size_z elements are covered (for 1D problems pass
+ * 1 as size_y and size_z) . when a count is bigger than
+ * max_compute_workgroups_per_dimension of the gpu device it is split and the
+ * rest is moved to the next dimension , so kernel codes which can receive such
+ * sizes must compute their element index from @builtin(num_workgroups) .
+ * kernel codes must skip the indices which are out of the size , because the
+ * last workgroups can be partially used
  */
-uintptr_t set_kernel_default_config(struct CKernel *kernel);
+enum GPUComputingStatus emcompute_set_workgroups_for_size(struct CKernel *kernel,
+                                                          uint32_t size_x,
+                                                          uint32_t size_y,
+                                                          uint32_t size_z);
 
 /**
  * the simple and compact function for sending
@@ -439,11 +1333,38 @@ uintptr_t set_kernel_default_config(struct CKernel *kernel);
  * unlike CUDA , you dont need to copy data to gpu manually , this function does it for you
  * in the most performant possible way
  *
+ * since v7.0.0 it returns GPUComputingStatus instead of -1 or panicking
+ *
+ * since v7.0.0 data_for_gpu is checked against the @group @binding layout
+ * of the kernel code before dispatching , mismatches are returned as
+ * InvalidArgument naming the group , binding and expected size
+ *
  * if you find any bug or any problem , help us to fix it -> https://github.com/SkillfulElectro/EMCompute.git
  */
-int32_t compute(struct CKernel *kernel,
-                struct GroupOfBinders *data_for_gpu,
-                uintptr_t gpu_data_len);
+enum GPUComputingStatus compute(struct CKernel *kernel,
+                                struct GroupOfBinders *data_for_gpu,
+                                uintptr_t gpu_data_len);
+
+/**
+ * since v7.0.0 works like compute function and sets push_constants_len
+ * bytes of push_constants to var<push_constant> of the kernel code before
+ * dispatching . push_constants field of GPUComputingConfig must be true and
+ * the gpu device must support push constants , otherwise UnsupportedFeature
+ * is returned . push_constants_len must be multiple of 4
+ */
+enum GPUComputingStatus compute_with_push_constants(struct CKernel *kernel,
+                                                    struct GroupOfBinders *data_for_gpu,
+                                                    uintptr_t gpu_data_len,
+                                                    const uint8_t *push_constants,
+                                                    uintptr_t push_constants_len);
+
+/**
+ * since v7.0.0 memory which API allocated for you (for example
+ * results of DataBinder with NewAllocation read_back_mode) must
+ * be freed with this function instead of free , len is the
+ * data_len of that memory
+ */
+enum GPUComputingStatus emcompute_free_bytes(uint8_t *data, uintptr_t len);
 
 /**
  * since version 2.0.0 api does
@@ -452,17 +1373,302 @@ int32_t compute(struct CKernel *kernel,
  * automatically , but in some cases
  * you might want to do it manually
  * so just call free_compute_cache();
+ * since v7.0.0 it destroys every EMContext , EMKernel , EMJob ,
+ * EMCommandList and EMMultiDevice handle , use emcompute_context_destroy
+ * for freeing only one of them
+ */
+enum GPUComputingStatus free_compute_cache(void);
+
+/**
+ * since v7.0.0 when a function returns anything other than Success
+ * you can call this function from the same thread to get the
+ * message describing the problem . returned string is owned by
+ * the api and stays valid until the next failing call on that thread ,
+ * NULL is returned when nothing failed yet
+ */
+const char *emcompute_last_error(void);
+
+/**
+ * since v7.0.0 when register_computing_kernel_code or other kernel
+ * registering function returns ShaderCompileError , this function
+ * returns array of the problems which were found in the code and writes
+ * its len to diagnostics_len . like emcompute_last_error it must be called
+ * from the same thread , the array is owned by the api and stays valid
+ * until the next failing call on that thread . NULL is returned when
+ * the last failing call has no diagnostics
+ */
+const struct GPUCompileDiagnostic *emcompute_last_compile_diagnostics(uintptr_t *diagnostics_len);
+
+/**
+ * since v7.0.0 writes what the entry point of registered kernel code
+ * needs to reflection : its bindings with their address space , access
+ * mode , element type and stride , its workgroup size and push constant size .
+ * so DataBinders can be checked or generated by your program
+ */
+enum GPUComputingStatus emcompute_kernel_reflect(struct EMKernel *kernel_code,
+                                                 struct GPUKernelReflection *reflection);
+
+/**
+ * since v7.0.0 frees memory of GPUKernelReflection
+ * which emcompute_kernel_reflect wrote
+ */
+enum GPUComputingStatus emcompute_free_kernel_reflection(struct GPUKernelReflection *reflection);
+
+/**
+ * since v7.0.0 buffers can be kept on gpu side between compute calls .
+ * this function creates zero initialized buffer with size bytes
+ * (rounded up to multiple of 4) on gpu resources of context
+ * and writes its index to buffer_index .
+ * for binding it set buffer_index field of DataBinder to this value
+ */
+enum GPUComputingStatus create_computing_buffer(struct EMContext *context,
+                                                uint64_t size,
+                                                uintptr_t *buffer_index);
+
+/**
+ * writes data_len bytes of data to the computing buffer starting
+ * from offset , offset and data_len must be multiples of 4
+ */
+enum GPUComputingStatus write_computing_buffer(struct EMContext *context,
+                                               uintptr_t buffer_index,
+                                               uint64_t offset,
+                                               const uint8_t *data,
+                                               uintptr_t data_len);
+
+/**
+ * reads data_len bytes of the computing buffer starting from offset
+ * to the memory which data points to , offset and data_len must be
+ * multiples of 4
+ */
+enum GPUComputingStatus read_computing_buffer(struct EMContext *context,
+                                              uintptr_t buffer_index,
+                                              uint64_t offset,
+                                              uint8_t *data,
+                                              uintptr_t data_len);
+
+/**
+ * changes size of the computing buffer to new_size bytes (rounded up
+ * to multiple of 4) , the old content is kept as much as it fits
+ * and the new part is zero initialized . buffer_index stays the same
+ */
+enum GPUComputingStatus resize_computing_buffer(struct EMContext *context,
+                                                uintptr_t buffer_index,
+                                                uint64_t new_size);
+
+/**
+ * frees the computing buffer , its buffer_index can not
+ * be used anymore
+ */
+enum GPUComputingStatus free_computing_buffer(struct EMContext *context, uintptr_t buffer_index);
+
+/**
+ * since v7.0.0 works like compute function but returns right after
+ * the task is submitted to the gpu , so host side can do other work
+ * while gpu side computes . handle of the task is written to job and
+ * the results are copied back to host memory of data_for_gpu when
+ * emcompute_job_poll or emcompute_job_wait finds out that the task is
+ * finished , so host memory of DataBinders must stay valid and unchanged
+ * until then . when callback is not NULL , it is called with user_data
+ * from another thread after the results are copied back .
+ * the handle must be destroyed with emcompute_job_destroy
+ */
+enum GPUComputingStatus compute_async(struct CKernel *kernel,
+                                      struct GroupOfBinders *data_for_gpu,
+                                      uintptr_t gpu_data_len,
+                                      GPUJobCallback callback,
+                                      void *user_data,
+                                      struct EMJob **job);
+
+/**
+ * since v7.0.0 checks the task of job without blocking , Success is
+ * returned when it is finished and its results are copied back ,
+ * JobPending when gpu side is still working on it , or the error
+ * which the task failed with
+ */
+enum GPUComputingStatus emcompute_job_poll(struct EMJob *job);
+
+/**
+ * since v7.0.0 blocks until the task of job is finished or timeout_ms
+ * milliseconds passed , negative timeout_ms waits without time limit .
+ * it returns the same values as emcompute_job_poll
+ */
+enum GPUComputingStatus emcompute_job_wait(struct EMJob *job, int64_t timeout_ms);
+
+/**
+ * since v7.0.0 frees the job handle , when the task is not finished
+ * yet its results are dropped and host memory is left untouched .
+ * tasks which have callback are still finished and their callback is
+ * called , so their host memory must stay valid until then
+ */
+enum GPUComputingStatus emcompute_job_destroy(struct EMJob *job);
+
+/**
+ * since v7.0.0 creates empty command list on context and writes
+ * its handle to list . command lists record several dispatches ,
+ * possibly of different kernel codes , and send all of them to the
+ * gpu in one submission , so the stages of a pipeline of kernels do
+ * not pay for uploading , submitting and reading back one by one
+ */
+enum GPUComputingStatus emcompute_command_list_create(struct EMContext *context,
+                                                      struct EMCommandList **list);
+
+/**
+ * since v7.0.0 records kernel with data_for_gpu like compute function
+ * does , but nothing is sent to the gpu until the list is run .
+ * dispatches run in the order they are recorded and each of them sees
+ * what the previous ones wrote to computing buffers , so data which
+ * flows between stages must be bound with buffer_index and it never
+ * leaves gpu side . host data of DataBinders is uploaded before the
+ * first dispatch and read back after the last one , so it must stay
+ * valid until the list is finished and it is not shared between dispatches
+ */
+enum GPUComputingStatus emcompute_command_list_dispatch(struct EMCommandList *list,
+                                                        struct CKernel *kernel,
+                                                        struct GroupOfBinders *data_for_gpu,
+                                                        uintptr_t gpu_data_len);
+
+/**
+ * since v7.0.0 records kernel like emcompute_command_list_dispatch and
+ * sets push_constants_len bytes of push_constants to var<push_constant>
+ * of the kernel code before dispatching it , see compute_with_push_constants
+ */
+enum GPUComputingStatus emcompute_command_list_dispatch_with_push_constants(struct EMCommandList *list,
+                                                                            struct CKernel *kernel,
+                                                                            struct GroupOfBinders *data_for_gpu,
+                                                                            uintptr_t gpu_data_len,
+                                                                            const uint8_t *push_constants,
+                                                                            uintptr_t push_constants_len);
+
+/**
+ * since v7.0.0 records kernel like emcompute_command_list_dispatch but
+ * x , y and z fields of kernel are ignored and number of workgroups is
+ * read as three uint32_t values from the computing buffer with
+ * indirect_buffer_index starting at byte indirect_offset when the dispatch
+ * runs , so an earlier dispatch of the list can write them on gpu side
+ * without reading anything back . indirect_offset must be multiple of 4
+ */
+enum GPUComputingStatus emcompute_command_list_dispatch_indirect(struct EMCommandList *list,
+                                                                 struct CKernel *kernel,
+                                                                 struct GroupOfBinders *data_for_gpu,
+                                                                 uintptr_t gpu_data_len,
+                                                                 uintptr_t indirect_buffer_index,
+                                                                 uint64_t indirect_offset);
+
+/**
+ * since v7.0.0 sends every recorded dispatch of list to the gpu in one
+ * submission and blocks until the results are copied back . after it
+ * returns the list is empty and new dispatches can be recorded on it
+ */
+enum GPUComputingStatus emcompute_command_list_run(struct EMCommandList *list);
+
+/**
+ * since v7.0.0 works like emcompute_command_list_run but returns right
+ * after the dispatches are submitted and writes handle of the task to
+ * job , see compute_async for callback , user_data and the handle
+ */
+enum GPUComputingStatus emcompute_command_list_run_async(struct EMCommandList *list,
+                                                         GPUJobCallback callback,
+                                                         void *user_data,
+                                                         struct EMJob **job);
+
+/**
+ * since v7.0.0 frees list and the dispatches which are recorded on it
+ * but not run yet , the handle can not be used anymore
+ */
+enum GPUComputingStatus emcompute_command_list_destroy(struct EMCommandList *list);
+
+/**
+ * since v7.0.0 creates empty multi device executor and writes its handle
+ * to multi_device . it runs one kernel code on several EMContexts at once ,
+ * so machines with more than one gpu can use all of them for one task
+ */
+enum GPUComputingStatus emcompute_multi_device_create(struct EMMultiDevice **multi_device);
+
+/**
+ * since v7.0.0 adds the device which context field of kernel points to ,
+ * kernel_code field must be registered on that context and it must be the
+ * same kernel code on every device (its bindings and workgroup size are
+ * compared with the first device) , x , y and z fields are ignored .
+ * multi_device keeps its own reference to the kernel code , so the EMKernel
+ * can be destroyed after it is added .
+ * weight is share of the range which the device gets compared to weights
+ * of the other devices , devices are indexed in the order they are added
+ */
+enum GPUComputingStatus emcompute_multi_device_add(struct EMMultiDevice *multi_device,
+                                                   struct CKernel *kernel,
+                                                   double weight);
+
+/**
+ * since v7.0.0 changes weight of the device which is added at index
+ */
+enum GPUComputingStatus emcompute_multi_device_set_weight(struct EMMultiDevice *multi_device,
+                                                          uintptr_t index,
+                                                          double weight);
+
+/**
+ * since v7.0.0 writes weights of the devices to weights in the order
+ * they are added , weights_len is len of weights and it must not be
+ * smaller than number of the added devices
+ */
+enum GPUComputingStatus emcompute_multi_device_weights(struct EMMultiDevice *multi_device,
+                                                       double *weights,
+                                                       uintptr_t weights_len);
+
+/**
+ * since v7.0.0 sets weights of the devices to the throughput which they
+ * had in the last emcompute_multi_device_compute , so faster devices get
+ * bigger parts next time . devices which had no part yet keep their weight
+ */
+enum GPUComputingStatus emcompute_multi_device_balance(struct EMMultiDevice *multi_device);
+
+/**
+ * since v7.0.0 binds var<uniform> of 4 uint32_t values at @group(group)
+ * @binding(bind) on every device : offset of its part , len of its part ,
+ * size of the whole range in the divided dimension and 0 for padding ,
+ * so kernel codes can find where their part of the range starts
+ */
+enum GPUComputingStatus emcompute_multi_device_set_range_binding(struct EMMultiDevice *multi_device,
+                                                                 uint32_t group,
+                                                                 uint32_t bind);
+
+/**
+ * since v7.0.0 divides range of size_x * size_y items between the devices
+ * proportionally to their weights , runs the parts on all of them at the
+ * same time and blocks until every result is copied back . 1D ranges
+ * (size_y is 1) are divided along x and 2D ranges into bands of rows along y .
+ * DataBinders which have split set to true are divided like the range , so
+ * their data_len must be multiple of the divided size , other DataBinders are
+ * given whole to every device and they must be read only . every device runs
+ * the kernel code on its part like it is the whole range and workgroups are
+ * computed like emcompute_set_workgroups_for_size does , computing buffers
+ * (buffer_index) can not be used because they live on one device . when one
+ * device fails , the parts of the other devices are still waited for and
+ * copied back , then the error of the first failed device is returned
+ */
+enum GPUComputingStatus emcompute_multi_device_compute(struct EMMultiDevice *multi_device,
+                                                       uint32_t size_x,
+                                                       uint32_t size_y,
+                                                       struct GroupOfBinders *data_for_gpu,
+                                                       uintptr_t gpu_data_len);
+
+/**
+ * since v7.0.0 frees multi_device , the EMContexts and kernel codes which
+ * are added to it are not freed . destroying one of those EMContexts
+ * destroys multi_device too
  */
-void free_compute_cache(void);
+enum GPUComputingStatus emcompute_multi_device_destroy(struct EMMultiDevice *multi_device);
 
 /**
- * this function returns GPUDevices of passed GPUComputingBackend
+ * this function returns GPUDevices of passed GPUComputingBackend .
+ * since v7.0.0 the GPUDevices is written to devices arg and
+ * GPUComputingStatus is returned
  */
-struct GPUDevices get_computing_gpu_infos(enum GPUComputingBackend backend);
+enum GPUComputingStatus get_computing_gpu_infos(enum GPUComputingBackend backend,
+                                                struct GPUDevices *devices);
 
 /**
  * this function is used for deallocating GPUDevices type from C side
  */
-void free_gpu_devices_infos(struct GPUDevices *devices);
+enum GPUComputingStatus free_gpu_devices_infos(struct GPUDevices *devices);
 
 #endif  /* EMCOMPUTE_H */
@@ -4,15 +4,14 @@
 #include "EMCompute.h"
 
 int main() {
-  GPUDevices infos = get_computing_gpu_infos(0);
-  printf("%s\n" , infos.infos[0].name);
+  GPUDevices infos = {0};
+  if (get_computing_gpu_infos(0 , &infos) == Success && infos.len > 0) {
+    printf("%s\n" , infos.infos[0].name);
+  }
   free_gpu_devices_infos(&infos);
 
   // Define the kernel
   CKernel kernel;
-  kernel.x = 60000;  // Number of workgroups in the x dimension
-  kernel.y = 1000;
-  kernel.z = 100;
 
   // WGSL code to perform element-wise addition of example_data and example_data0
   const char* code = 
@@ -20,14 +19,27 @@ int main() {
     "@group(0)@binding(1) var<storage, read_write> v_indices0: array<u32>; "
     "@compute @workgroup_size(10 , 1 , 1)" 
     "fn main(@builtin(global_invocation_id) global_id: vec3<u32>) { "
-    "  let idx = global_id.x % 60000; "
-    "   "
+    "  let idx = global_id.x; "
+    "  if (idx >= arrayLength(&v_indices)) { return; } "
     "v_indices[idx] = v_indices[idx] + v_indices0[idx]; "
     "  "
     "}";
 
-  uintptr_t index = set_kernel_default_config(&kernel);
-  kernel.kernel_code_index = register_computing_kernel_code(index , code , "main");
+  // since v7.0.0 every function returns GPUComputingStatus
+  if (set_kernel_default_config(&kernel) != Success) {
+    printf("%s\n" , emcompute_last_error());
+    return 1;
+  }
+  if (register_computing_kernel_code(kernel.context , code , "main" , &kernel.kernel_code) != Success) {
+    printf("%s\n" , emcompute_last_error());
+    return 1;
+  }
+  // since v7.0.0 x , y and z fields of kernel can be computed from
+  // number of elements and @workgroup_size of the kernel code
+  if (emcompute_set_workgroups_for_size(&kernel , 60000 , 1 , 1) != Success) {
+    printf("%s\n" , emcompute_last_error());
+    return 1;
+  }
 
 
 
@@ -41,15 +53,23 @@ int main() {
   }
 
   // Bind data
-  DataBinder data;
+  DataBinder data = {0}; // fields which are not set below (texture , sampler , split) stay zero
   data.bind = 0;
   data.data = (uint8_t **)&example_data;
   data.data_len = sizeof(uint32_t)*60000/sizeof(uint8_t);
+  data.buffer_index = -1; // data field is used instead of computing buffer
+  data.access = ReadWrite;
+  data.read_back_mode = InPlace; // results are copied to example_data
+  data.kind = Storage; // var<storage> in kernel code
 
-  DataBinder data0;
+  DataBinder data0 = {0};
   data0.bind = 1;
   data0.data = (uint8_t **)&example_data0;
   data0.data_len = sizeof(uint32_t)*60000/sizeof(uint8_t);
+  data0.buffer_index = -1;
+  data0.access = ReadOnly; // example_data0 is only input , so it is not read back
+  data0.read_back_mode = InPlace;
+  data0.kind = Storage;
 
   DataBinder group0[] = {data, data0};
   GroupOfBinders wrapper;
@@ -59,7 +79,9 @@ int main() {
 
   GroupOfBinders groups[] = {wrapper};
   //for (int i = 0 ; i < 10000 ;++i){
-    compute(&kernel, groups, 1);
+    if (compute(&kernel, groups, 1) != Success) {
+      printf("%s\n" , emcompute_last_error());
+    }
   //}
 
   
@@ -70,8 +92,8 @@ int main() {
 
   free(example_data0);
   free(example_data);
-  free_compute_cache();
+  emcompute_kernel_destroy(kernel.kernel_code);
+  emcompute_context_destroy(kernel.context);
 
   return 0;
 }
-
@@ -0,0 +1,218 @@
+use std::sync::Arc;
+
+use bytemuck::Pod;
+
+use crate::GPUDataAccess;
+use crate::buffer::Buffer;
+use crate::texture::{GPUTextureInfo , GPUSamplerInfo};
+
+
+// host memory which is bound to the kernel , inputs
+// only need shared access to it
+pub(crate) enum HostData<'a> {
+    Shared(&'a [u8]) ,
+    Exclusive(&'a mut [u8]) ,
+}
+
+impl HostData<'_> {
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        match self {
+            HostData::Shared(data) => data ,
+            HostData::Exclusive(data) => data ,
+        }
+    }
+
+    pub(crate) fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        match self {
+            HostData::Shared(_) => None ,
+            HostData::Exclusive(data) => Some(data) ,
+        }
+    }
+}
+
+pub(crate) enum BindingResource<'a> {
+    Buffer {
+        raw : Arc<wgpu::Buffer> ,
+        device : Arc<wgpu::Device> ,
+    } ,
+    Host {
+        data : HostData<'a> ,
+        access : GPUDataAccess ,
+    } ,
+    Uniform {
+        data : &'a [u8] ,
+    } ,
+    // sampled textures are ReadOnly storage textures
+    // which are bound to texture_2d or texture_3d
+    Texture {
+        data : HostData<'a> ,
+        access : GPUDataAccess ,
+        info : GPUTextureInfo ,
+        sampled : bool ,
+    } ,
+    Sampler {
+        info : GPUSamplerInfo ,
+    } ,
+}
+
+/// Binding is the safe Rust side of DataBinder , it binds
+/// host data or a Buffer to @group(group) @binding(bind)
+/// of the kernel code for Context::compute
+pub struct Binding<'a> {
+    pub(crate) group : u32 ,
+    pub(crate) bind : u32 ,
+    pub(crate) resource : BindingResource<'a> ,
+}
+
+impl<'a> Binding<'a> {
+    /// binds Buffer which lives on gpu side , nothing is
+    /// uploaded or read back . it can be bound to var<storage>
+    /// or var<uniform> of the kernel code
+    pub fn buffer<T : Pod>(group : u32 , bind : u32 , buffer : &Buffer<T>) -> Self {
+        Binding {
+            group ,
+            bind ,
+            resource : BindingResource::Buffer {
+                raw : Arc::clone(&buffer.raw) ,
+                device : Arc::clone(&buffer.context.device) ,
+            } ,
+        }
+    }
+
+    /// binds data as input of the kernel , it is uploaded
+    /// but never read back (GPUDataAccess::ReadOnly)
+    pub fn input<T : Pod>(group : u32 , bind : u32 , data : &'a [T]) -> Self {
+        Binding {
+            group ,
+            bind ,
+            resource : BindingResource::Host {
+                data : HostData::Shared(bytemuck::cast_slice(data)) ,
+                access : GPUDataAccess::ReadOnly ,
+            } ,
+        }
+    }
+
+    /// binds data as output of the kernel , it is not uploaded
+    /// and results are copied to it (GPUDataAccess::WriteOnly)
+    pub fn output<T : Pod>(group : u32 , bind : u32 , data : &'a mut [T]) -> Self {
+        Binding {
+            group ,
+            bind ,
+            resource : BindingResource::Host {
+                data : HostData::Exclusive(bytemuck::cast_slice_mut(data)) ,
+                access : GPUDataAccess::WriteOnly ,
+            } ,
+        }
+    }
+
+    /// binds data which is uploaded and then replaced by
+    /// the results (GPUDataAccess::ReadWrite)
+    pub fn read_write<T : Pod>(group : u32 , bind : u32 , data : &'a mut [T]) -> Self {
+        Binding {
+            group ,
+            bind ,
+            resource : BindingResource::Host {
+                data : HostData::Exclusive(bytemuck::cast_slice_mut(data)) ,
+                access : GPUDataAccess::ReadWrite ,
+            } ,
+        }
+    }
+
+    /// binds data to var<uniform> of the kernel code , it is
+    /// uploaded into uniform buffer and never read back
+    pub fn uniform<T : Pod>(group : u32 , bind : u32 , data : &'a [T]) -> Self {
+        Binding {
+            group ,
+            bind ,
+            resource : BindingResource::Uniform {
+                data : bytemuck::cast_slice(data) ,
+            } ,
+        }
+    }
+
+    fn texture(group : u32 , bind : u32 , info : GPUTextureInfo , data : HostData<'a> , access : GPUDataAccess , sampled : bool) -> Self {
+        Binding {
+            group ,
+            bind ,
+            resource : BindingResource::Texture {
+                data ,
+                access ,
+                info ,
+                sampled ,
+            } ,
+        }
+    }
+
+    /// binds data as texture_2d or texture_3d of the kernel code ,
+    /// it is uploaded and never read back . data must be tightly
+    /// packed texels which match info
+    pub fn sampled_texture<T : Pod>(group : u32 , bind : u32 , info : GPUTextureInfo , data : &'a [T]) -> Self {
+        Self::texture(group , bind , info , HostData::Shared(bytemuck::cast_slice(data)) , GPUDataAccess::ReadOnly , true)
+    }
+
+    /// binds data as read only texture_storage_2d or texture_storage_3d
+    /// of the kernel code , it is uploaded but never read back
+    pub fn storage_texture_input<T : Pod>(group : u32 , bind : u32 , info : GPUTextureInfo , data : &'a [T]) -> Self {
+        Self::texture(group , bind , info , HostData::Shared(bytemuck::cast_slice(data)) , GPUDataAccess::ReadOnly , false)
+    }
+
+    /// binds data as write only texture_storage_2d or texture_storage_3d
+    /// of the kernel code , it is not uploaded and texels are copied back to it
+    pub fn storage_texture_output<T : Pod>(group : u32 , bind : u32 , info : GPUTextureInfo , data : &'a mut [T]) -> Self {
+        Self::texture(group , bind , info , HostData::Exclusive(bytemuck::cast_slice_mut(data)) , GPUDataAccess::WriteOnly , false)
+    }
+
+    /// binds data as read_write texture_storage_2d or texture_storage_3d
+    /// of the kernel code , it is uploaded and then replaced by the results
+    pub fn storage_texture<T : Pod>(group : u32 , bind : u32 , info : GPUTextureInfo , data : &'a mut [T]) -> Self {
+        Self::texture(group , bind , info , HostData::Exclusive(bytemuck::cast_slice_mut(data)) , GPUDataAccess::ReadWrite , false)
+    }
+
+    /// binds sampler of the kernel code
+    pub fn sampler(group : u32 , bind : u32 , info : GPUSamplerInfo) -> Self {
+        Binding {
+            group ,
+            bind ,
+            resource : BindingResource::Sampler {
+                info ,
+            } ,
+        }
+    }
+
+    // copy of the binding which borrows the same data , bindings
+    // which can be written or live on one device can not be shared
+    pub(crate) fn share(&self) -> Option<Binding<'a>> {
+        let resource = match &self.resource {
+            BindingResource::Host { data : HostData::Shared(data) , access } => BindingResource::Host {
+                data : HostData::Shared(data) ,
+                access : *access ,
+            } ,
+            BindingResource::Uniform { data } => BindingResource::Uniform {
+                data ,
+            } ,
+            BindingResource::Texture { data : HostData::Shared(data) , access , info , sampled } => BindingResource::Texture {
+                data : HostData::Shared(data) ,
+                access : *access ,
+                info : *info ,
+                sampled : *sampled ,
+            } ,
+            BindingResource::Sampler { info } => BindingResource::Sampler {
+                info : *info ,
+            } ,
+            _ => return None ,
+        };
+
+        Some(Binding {
+            group : self.group ,
+            bind : self.bind ,
+            resource ,
+        })
+    }
+
+    pub(crate) fn needs_read_back(&self) -> bool {
+        match &self.resource {
+            BindingResource::Buffer { .. } | BindingResource::Uniform { .. } | BindingResource::Sampler { .. } => false ,
+            BindingResource::Host { access , .. } | BindingResource::Texture { access , .. } => *access != GPUDataAccess::ReadOnly ,
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use bytemuck::Pod;
+
+use crate::context::Context;
+use crate::error::{with_error_scope , GPUComputingError , GPUComputingStatus};
+
+
+pub(crate) fn align_to_copy(size : u64) -> u64 {
+    size.div_ceil(wgpu::COPY_BUFFER_ALIGNMENT) * wgpu::COPY_BUFFER_ALIGNMENT
+}
+
+// buffers which live on gpu side are used as storage , uniform
+// or indirect buffers and they must be copyable for write , read
+// and resize
+fn buffer_usages() -> wgpu::BufferUsages {
+    wgpu::BufferUsages::STORAGE
+        | wgpu::BufferUsages::UNIFORM
+        | wgpu::BufferUsages::INDIRECT
+        | wgpu::BufferUsages::COPY_DST
+        | wgpu::BufferUsages::COPY_SRC
+}
+
+// byte offset and byte len of the elements offset..offset + len ,
+// the offset must be aligned for copies but the len can be rounded
+// up when the range ends at the end of the Buffer , because its
+// gpu side allocation is rounded up to COPY_BUFFER_ALIGNMENT
+fn byte_range(offset : usize , len : usize , buffer_len : usize , elem_size : u64) -> Result<(u64 , u64) , GPUComputingError> {
+    if offset.checked_add(len).is_none_or(|end| end > buffer_len) {
+        return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : range {}..{} is out of Buffer with len {}" , offset , offset.saturating_add(len) , buffer_len)));
+    }
+
+    let byte_offset = offset as u64 * elem_size;
+    let byte_len = len as u64 * elem_size;
+    let to_end = offset + len == buffer_len;
+    if !byte_offset.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT) || !(to_end || byte_len.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT)) {
+        return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : byte offset ({}) and byte len ({}) of Buffer operations must be multiples of {} , only ranges which end at the end of the Buffer can have other byte lens" , byte_offset , byte_len , wgpu::COPY_BUFFER_ALIGNMENT)));
+    }
+
+    Ok((byte_offset , byte_len))
+}
+
+/// maps staging_buffer which is already filled by a submitted
+/// copy and passes its content to read
+pub(crate) fn map_staging<F : FnOnce(&[u8])>(device : &wgpu::Device , staging_buffer : &wgpu::Buffer , read : F) -> Result<() , GPUComputingError> {
+    let buffer_slice = staging_buffer.slice(..);
+    let (sender, receiver) = flume::bounded(1);
+    buffer_slice.map_async(wgpu::MapMode::Read, move |v| sender.send(v).unwrap());
+
+    // callback of map_async is called by this poll , so it
+    // has no result only when the device did not finish the work
+    let maintain = device.poll(wgpu::Maintain::wait());
+
+    let mapped = receiver.try_recv().map_err(|_| GPUComputingError::new(GPUComputingStatus::MapFailed , match maintain {
+        wgpu::MaintainResult::SubmissionQueueEmpty => "ERROR : failed to read back data from gpu , staging buffer is not mapped although no work is left on the device" ,
+        wgpu::MaintainResult::Ok => "ERROR : failed to read back data from gpu , timed out waiting for the device to map staging buffer" ,
+    }))?;
+
+    mapped.map_err(|err| GPUComputingError::new(GPUComputingStatus::MapFailed , format!("ERROR : failed to read back data from gpu , {}" , err)))?;
+
+    let mapped_data = buffer_slice.get_mapped_range();
+    read(&mapped_data);
+    drop(mapped_data);
+    staging_buffer.unmap();
+
+    Ok(())
+}
+
+/// maps staging_buffer and copies its first out.len() bytes to out
+pub(crate) fn map_and_copy(device : &wgpu::Device , staging_buffer : &wgpu::Buffer , out : &mut [u8]) -> Result<() , GPUComputingError> {
+    map_staging(device , staging_buffer , |mapped_data| {
+        out.copy_from_slice(&mapped_data[..out.len()]);
+    })
+}
+
+#[derive(Debug)]
+/// Buffer keeps len elements of T on gpu side between compute
+/// calls , it is bound with Binding::buffer and its content can
+/// be changed or read with write and read functions . writing
+/// is cheap , so small Buffers are good for var<uniform>
+/// parameters which change between compute calls
+pub struct Buffer<T : Pod> {
+    pub(crate) raw : Arc<wgpu::Buffer> ,
+    pub(crate) context : Context ,
+    len : usize ,
+    _marker : PhantomData<T> ,
+}
+
+impl<T : Pod> Buffer<T> {
+    fn create_raw(context : &Context , len : usize) -> Result<wgpu::Buffer , GPUComputingError> {
+        let size = (len as u64).checked_mul(std::mem::size_of::<T>() as u64)
+            .filter(|size| *size > 0)
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : invalid len {} for Buffer" , len)))?;
+
+        with_error_scope(&context.device , || {
+            context.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Computing Buffer"),
+                size : align_to_copy(size) ,
+                usage: buffer_usages(),
+                mapped_at_creation: false,
+            })
+        })
+    }
+
+    /// creates zero initialized Buffer with len elements
+    pub fn new(context : &Context , len : usize) -> Result<Self , GPUComputingError> {
+        Ok(Buffer {
+            raw : Arc::new(Self::create_raw(context , len)?) ,
+            context : context.clone() ,
+            len ,
+            _marker : PhantomData ,
+        })
+    }
+
+    /// creates Buffer which contains copy of data
+    pub fn from_slice(context : &Context , data : &[T]) -> Result<Self , GPUComputingError> {
+        let buffer = Self::new(context , data.len())?;
+        buffer.write(0 , data)?;
+        Ok(buffer)
+    }
+
+    /// number of elements of the Buffer
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Context which the Buffer is created on
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn check_range(&self , offset : usize , len : usize) -> Result<(u64 , u64) , GPUComputingError> {
+        byte_range(offset , len , self.len , std::mem::size_of::<T>() as u64)
+    }
+
+    /// writes data to the Buffer starting from element offset ,
+    /// offset must be multiple of 4 bytes and data too unless it
+    /// reaches the end of the Buffer
+    pub fn write(&self , offset : usize , data : &[T]) -> Result<() , GPUComputingError> {
+        let (byte_offset , byte_len) = self.check_range(offset , data.len())?;
+
+        let data : &[u8] = bytemuck::cast_slice(data);
+        // the padding only covers the rounded up end of the allocation
+        let padded : Vec<u8>;
+        let data = if byte_len.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT) {
+            data
+        } else {
+            padded = data.iter()
+                .copied()
+                .chain(std::iter::repeat(0))
+                .take(align_to_copy(byte_len) as usize)
+                .collect();
+            &padded
+        };
+
+        with_error_scope(&self.context.device , || {
+            self.context.queue.write_buffer(&self.raw , byte_offset , data);
+            self.context.queue.submit([]);
+        })
+    }
+
+    /// reads out.len() elements of the Buffer starting from element
+    /// offset , offset must be multiple of 4 bytes and out too unless
+    /// it reaches the end of the Buffer
+    pub fn read_into(&self , offset : usize , out : &mut [T]) -> Result<() , GPUComputingError> {
+        let (byte_offset , byte_len) = self.check_range(offset , out.len())?;
+        if byte_len == 0 {
+            return Ok(());
+        }
+        let copy_len = align_to_copy(byte_len);
+
+        let device = &self.context.device;
+
+        let staging_buffer = with_error_scope(device , || {
+            let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                label: None ,
+                size : copy_len ,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+
+            let mut encoder =
+                device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            encoder.copy_buffer_to_buffer(&self.raw , byte_offset , &staging_buffer , 0 , copy_len);
+            self.context.queue.submit(Some(encoder.finish()));
+
+            staging_buffer
+        })?;
+
+        map_and_copy(device , &staging_buffer , bytemuck::cast_slice_mut(out))
+    }
+
+    /// reads all of the elements of the Buffer
+    pub fn read(&self) -> Result<Vec<T> , GPUComputingError> {
+        let mut out = vec![T::zeroed(); self.len];
+        self.read_into(0 , &mut out)?;
+        Ok(out)
+    }
+
+    /// changes number of elements of the Buffer to len , the old content
+    /// is kept as much as it fits and the new part is zero initialized
+    pub fn resize(&mut self , len : usize) -> Result<() , GPUComputingError> {
+        let new_raw = Self::create_raw(&self.context , len)?;
+
+        let device = &self.context.device;
+        with_error_scope(device , || {
+            let mut encoder =
+                device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            encoder.copy_buffer_to_buffer(&self.raw , 0 , &new_raw , 0 , self.raw.size().min(new_raw.size()));
+            self.context.queue.submit(Some(encoder.finish()));
+        })?;
+
+        self.raw = Arc::new(new_raw);
+        self.len = len;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligned_middle_range_is_accepted() {
+        assert_eq!(byte_range(2 , 3 , 10 , 4).unwrap() , (8 , 12));
+        assert_eq!(byte_range(4 , 4 , 10 , 1).unwrap() , (4 , 4));
+    }
+
+    #[test]
+    fn unaligned_len_is_accepted_at_the_end() {
+        assert_eq!(byte_range(4 , 3 , 7 , 1).unwrap() , (4 , 3));
+        assert_eq!(byte_range(0 , 5 , 5 , 2).unwrap() , (0 , 10));
+    }
+
+    #[test]
+    fn unaligned_middle_range_is_rejected() {
+        let err = byte_range(4 , 3 , 10 , 1).unwrap_err();
+        assert_eq!(err.status , GPUComputingStatus::InvalidArgument);
+        assert!(err.message.contains("must be multiples of 4") , "{}" , err.message);
+
+        // unaligned offsets are rejected even at the end
+        let err = byte_range(1 , 9 , 10 , 1).unwrap_err();
+        assert_eq!(err.status , GPUComputingStatus::InvalidArgument);
+    }
+
+    #[test]
+    fn overflowing_range_is_rejected() {
+        let err = byte_range(usize::MAX , 2 , 10 , 4).unwrap_err();
+        assert_eq!(err.status , GPUComputingStatus::InvalidArgument);
+        assert!(err.message.contains("is out of Buffer with len 10") , "{}" , err.message);
+
+        assert!(byte_range(8 , 3 , 10 , 4).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use bytemuck::Pod;
+
+use crate::context::{Context , DispatchInfo , Workgroups};
+use crate::kernel::Kernel;
+use crate::binding::Binding;
+use crate::buffer::Buffer;
+use crate::job::Job;
+use crate::error::GPUComputingError;
+
+
+// recorded dispatch of a CommandList
+struct Dispatch<'a> {
+    kernel : Kernel ,
+    workgroups : Workgroups ,
+    bindings : Vec<Binding<'a>> ,
+    push_constants : Option<Vec<u8>> ,
+}
+
+/// CommandList records several dispatches , possibly of different
+/// Kernels , and sends all of them to the gpu in one submission .
+/// dispatches run in the order they are recorded and every dispatch
+/// sees what the previous ones wrote to Buffers , so data which flows
+/// between stages must be bound with Binding::buffer and it never
+/// leaves gpu side . host data of each dispatch is uploaded before
+/// the first dispatch and read back after the last one
+pub struct CommandList<'a> {
+    context : Context ,
+    dispatches : Vec<Dispatch<'a>> ,
+}
+
+impl<'a> CommandList<'a> {
+    /// creates empty CommandList which runs on context
+    pub fn new(context : &Context) -> Self {
+        CommandList {
+            context : context.clone() ,
+            dispatches : Vec::new() ,
+        }
+    }
+
+    /// records kernel with workgroups number of workgroups ,
+    /// bindings work like bindings of Context::compute
+    pub fn dispatch(&mut self , kernel : &Kernel , workgroups : [u32; 3] , bindings : Vec<Binding<'a>>) -> Result<() , GPUComputingError> {
+        self.record(kernel , Workgroups::Direct(workgroups) , bindings , None)
+    }
+
+    /// records kernel like dispatch and sets push_constants to
+    /// var<push_constant> of the kernel code before dispatching it
+    pub fn dispatch_with_push_constants<T : Pod>(&mut self , kernel : &Kernel , workgroups : [u32; 3] , bindings : Vec<Binding<'a>> , push_constants : &[T]) -> Result<() , GPUComputingError> {
+        self.record(kernel , Workgroups::Direct(workgroups) , bindings , Some(bytemuck::cast_slice(push_constants).to_vec()))
+    }
+
+    /// records kernel which reads its number of workgroups in x , y and z
+    /// dimensions as three u32 values from indirect_buffer starting at
+    /// byte offset when it runs , so an earlier dispatch of the list can
+    /// compute them on gpu side . offset must be multiple of 4
+    pub fn dispatch_indirect<T : Pod>(&mut self , kernel : &Kernel , indirect_buffer : &Buffer<T> , offset : u64 , bindings : Vec<Binding<'a>>) -> Result<() , GPUComputingError> {
+        self.record(kernel , Self::indirect(indirect_buffer , offset) , bindings , None)
+    }
+
+    /// dispatch_indirect with push constants , see dispatch_with_push_constants
+    pub fn dispatch_indirect_with_push_constants<T : Pod , P : Pod>(&mut self , kernel : &Kernel , indirect_buffer : &Buffer<T> , offset : u64 , bindings : Vec<Binding<'a>> , push_constants : &[P]) -> Result<() , GPUComputingError> {
+        self.record(kernel , Self::indirect(indirect_buffer , offset) , bindings , Some(bytemuck::cast_slice(push_constants).to_vec()))
+    }
+
+    fn indirect<T : Pod>(indirect_buffer : &Buffer<T> , offset : u64) -> Workgroups {
+        Workgroups::Indirect {
+            buffer : Arc::clone(&indirect_buffer.raw) ,
+            device : Arc::clone(&indirect_buffer.context.device) ,
+            offset ,
+        }
+    }
+
+    pub(crate) fn record(&mut self , kernel : &Kernel , workgroups : Workgroups , bindings : Vec<Binding<'a>> , push_constants : Option<Vec<u8>>) -> Result<() , GPUComputingError> {
+        self.context.check_same_device(&kernel.device)?;
+        if let Workgroups::Indirect { device , .. } = &workgroups {
+            self.context.check_same_device(device)?;
+        }
+
+        self.dispatches.push(Dispatch {
+            kernel : kernel.clone() ,
+            workgroups ,
+            bindings ,
+            push_constants ,
+        });
+
+        Ok(())
+    }
+
+    /// number of recorded dispatches
+    pub fn len(&self) -> usize {
+        self.dispatches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dispatches.is_empty()
+    }
+
+    /// submits every recorded dispatch and returns right away ,
+    /// results are copied back when the returned Job is finished
+    pub fn submit(self) -> Result<Job<'a> , GPUComputingError> {
+        let dispatches : Vec<DispatchInfo> = self.dispatches.iter()
+            .map(|dispatch| DispatchInfo {
+                kernel : &dispatch.kernel ,
+                workgroups : dispatch.workgroups.clone() ,
+                bindings : &dispatch.bindings ,
+                push_constants : dispatch.push_constants.as_deref() ,
+            })
+            .collect();
+
+        let submission = self.context.submit(&dispatches)?;
+        drop(dispatches);
+
+        let bindings = self.dispatches.into_iter()
+            .flat_map(|dispatch| dispatch.bindings)
+            .collect();
+
+        Ok(Job::new(bindings , submission))
+    }
+
+    /// submits every recorded dispatch and blocks until
+    /// the results are copied back
+    pub fn run(self) -> Result<() , GPUComputingError> {
+        self.submit()?.wait(None)?;
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use crate::GPUDeviceSelector;
+
 #[repr(C)]
 #[derive(Clone , Debug)]
 /// computing backends of the api 
@@ -70,6 +72,12 @@ pub enum GPUSpeedSettings {
     /// will be supported in next versions , for now it is equal to 
     /// low_speed
     custom_speed = 3 ,
+    /// since v7.0.0 gpu_speed_custom of GPUCustomSettings is used but
+    /// its zero fields are taken from default_speed limits and every
+    /// limit is clamped to what the gpu device supports , so partially
+    /// filled GPUSpeedCustom works on every device .
+    /// emcompute_context_limits returns the limits which were used
+    clamped_custom_speed = 4 ,
 }
 
 #[repr(C)]
@@ -95,6 +103,32 @@ pub struct GPUComputingConfig {
     /// free_gpu_devices_infos function must be used from C side of the program to deallocate 
     /// recived gpu infos , in Rust RAII will take care of it 
     /// ```
-    /// if it sets to negative value , API will automatically choose the gpu device
+    /// if it sets to negative value , API will automatically choose the gpu device .
+    /// since v7.0.0 it is ignored when device_selectors_len is not 0 , order of
+    /// the list can change with driver updates so device_selectors are preferred
     pub gpu_index_in_backend_group : i64 ,
+    /// since v7.0.0 if it is set to true and the gpu device supports 
+    /// push constants , they are enabled with max_push_constant_size 
+    /// of the device (for custom_speed max_push_constant_size of GPUSpeedCustom 
+    /// is used) , so kernel codes can use var<push_constant> and 
+    /// compute_with_push_constants function
+    pub push_constants : bool ,
+    /// since v7.0.0 GPU_FEATURE bits of the features which must be
+    /// enabled , when the gpu device misses one of them UnsupportedFeature
+    /// is returned and other bits than GPU_FEATURE bits are InvalidArgument .
+    /// emcompute_context_features returns what is enabled
+    pub required_features : u64 ,
+    /// since v7.0.0 GPU_FEATURE bits of the features which are enabled
+    /// only when the gpu device supports them , unknown bits are ignored
+    pub optional_features : u64 ,
+    /// since v7.0.0 array of GPUDeviceSelector which picks the gpu device
+    /// by its type , vendor , device id or name . selectors are tried in
+    /// order and the first one which matches a device of the backend is
+    /// used , between matching devices discrete gpus are picked first .
+    /// when none of them matches AdapterNotFound is returned . only
+    /// create_computing_gpu_resources reads it , Rust side passes
+    /// DeviceSelectors to Context::with_device_selectors instead
+    pub device_selectors : *const GPUDeviceSelector ,
+    /// len of device_selectors , 0 means device_selectors is not used
+    pub device_selectors_len : usize ,
 }
@@ -0,0 +1,676 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use bytemuck::Pod;
+use wgpu::util::DeviceExt;
+
+use crate::
+{GPUComputingBackend ,
+    GPUPowerSettings ,
+    GPUSpeedSettings ,
+    GPUMemorySettings ,
+    GPUComputingConfig ,
+    GPUCustomSettings ,
+    GPUSpeedCustom ,
+    GPUDataAccess};
+
+use crate::features::{bits_to_features , features_to_bits , unknown_feature_bits};
+use crate::gpu_device::{select_adapter , DeviceSelector};
+use crate::error::{set_last_error , with_error_scope , GPUComputingError , GPUComputingStatus};
+use crate::kernel::Kernel;
+use crate::binding::{Binding , BindingResource};
+use crate::buffer::align_to_copy;
+use crate::job::{Job , Submission};
+use crate::texture::GPUTextureInfo;
+
+
+#[derive(Clone , Debug)]
+/// Context is the safe Rust side of gpu resources which
+/// create_computing_gpu_resources allocates for C side ,
+/// Kernels and Buffers are created on it and it sends
+/// computing tasks to the gpu . cloning it is cheap and
+/// all of the clones use the same gpu device
+pub struct Context {
+    pub(crate) device : Arc<wgpu::Device> ,
+    pub(crate) queue : Arc<wgpu::Queue> ,
+}
+
+impl Context {
+    /// allocates gpu resources which match config , customize
+    /// is only checked for custom_speed and custom_memory settings .
+    /// device_selectors of config are ignored , use
+    /// with_device_selectors instead
+    pub fn new(config : &GPUComputingConfig , customize : &GPUCustomSettings) -> Result<Self , GPUComputingError> {
+        Self::with_device_selectors(config , customize , &[])
+    }
+
+    /// works like new but the gpu device is picked by selectors
+    /// when it is not empty : selectors are tried in order and the
+    /// first one which matches a device of the backend is used ,
+    /// between matching devices discrete gpus are picked first .
+    /// when none of them matches AdapterNotFound is returned
+    pub fn with_device_selectors(config : &GPUComputingConfig , customize : &GPUCustomSettings , selectors : &[DeviceSelector]) -> Result<Self , GPUComputingError> {
+        // unknown optional bits are ignored , but a required
+        // feature must never be dropped without an error
+        let unknown_bits = unknown_feature_bits(config.required_features);
+        if unknown_bits != 0 {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : required_features has bits {:#x} which are not GPU_FEATURE bits" , unknown_bits)));
+        }
+
+
+        let backend = match config.backend {
+                GPUComputingBackend::vulkan => {
+                    wgpu::Backends::VULKAN
+                },
+                GPUComputingBackend::opengl => {
+                    wgpu::Backends::GL
+                },
+                GPUComputingBackend::all => {
+                    wgpu::Backends::all()
+                },
+                GPUComputingBackend::default_backend => {
+                    wgpu::Backends::default()
+                },
+                GPUComputingBackend::metal => {
+                    wgpu::Backends::METAL
+                },
+                GPUComputingBackend::direct_x12 => {
+                    wgpu::Backends::DX12
+                },
+                GPUComputingBackend::highest_support => {
+                    wgpu::Backends::PRIMARY
+                },
+                GPUComputingBackend::lowest_support => {
+                    wgpu::Backends::SECONDARY
+                },
+                GPUComputingBackend::webgpu => {
+                    wgpu::Backends::BROWSER_WEBGPU
+                },
+            };
+
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor{
+            backends : backend ,
+            ..Default::default()
+        });
+
+
+        let adapter = if !selectors.is_empty() {
+            select_adapter(instance.enumerate_adapters(backend) , selectors)?
+
+        }else if config.gpu_index_in_backend_group < 0 {
+            pollster::block_on(instance
+            .request_adapter(&wgpu::RequestAdapterOptions{
+                power_preference : match config.power {
+                    GPUPowerSettings::none => {
+                        wgpu::PowerPreference::None
+                    },
+                    GPUPowerSettings::LowPower => {
+                        wgpu::PowerPreference::LowPower
+                    },
+                    GPUPowerSettings::HighPerformance => {
+                        wgpu::PowerPreference::HighPerformance
+                    },
+                },
+                ..Default::default()
+            }))
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::AdapterNotFound , "ERROR : could not find gpu device which matches your configs"))?
+
+        }else {
+            let mut adapters = instance.enumerate_adapters(backend);
+            let index = config.gpu_index_in_backend_group as usize;
+            if adapters.len() <= index {
+                return Err(GPUComputingError::new(GPUComputingStatus::AdapterNotFound , format!("ERROR : gpu_index_in_backend_group is {} but only {} gpu devices found for the backend" , index , adapters.len())));
+            }
+            adapters.swap_remove(index)
+        };
+
+
+        let mut required_limits = match config.speed {
+            GPUSpeedSettings::lowest_speed => {
+                wgpu::Limits::downlevel_webgl2_defaults()
+            },
+            GPUSpeedSettings::low_speed => {
+                wgpu::Limits::downlevel_defaults()
+            },
+            GPUSpeedSettings::custom_speed => {
+
+                customize.gpu_speed_custom.to_gpu_limits()
+            },
+            GPUSpeedSettings::clamped_custom_speed => {
+                customize.gpu_speed_custom.to_clamped_gpu_limits(&adapter.limits())
+            },
+            GPUSpeedSettings::default_speed => {
+                wgpu::Limits::default()
+            },
+        };
+
+        let supported_features = adapter.features();
+
+        let mut required_features = bits_to_features(config.required_features);
+        let missing_features = required_features - supported_features;
+        if !missing_features.is_empty() {
+            return Err(GPUComputingError::new(GPUComputingStatus::UnsupportedFeature , format!("ERROR : the gpu device does not support required features {:?}" , missing_features)));
+        }
+
+        required_features |= bits_to_features(config.optional_features) & supported_features;
+
+        if config.push_constants {
+            required_features |= wgpu::Features::PUSH_CONSTANTS & supported_features;
+        }
+
+        // custom push constant size is kept , zero of
+        // clamped_custom_speed means the default
+        let custom_push_constant_size = match config.speed {
+            GPUSpeedSettings::custom_speed => true ,
+            GPUSpeedSettings::clamped_custom_speed => customize.gpu_speed_custom.max_push_constant_size != 0 ,
+            _ => false ,
+        };
+        if required_features.contains(wgpu::Features::PUSH_CONSTANTS) && !custom_push_constant_size {
+            required_limits.max_push_constant_size = adapter.limits().max_push_constant_size;
+        }
+
+        let (device, queue) = pollster::block_on(adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: None,
+                    required_features ,
+                    required_limits ,
+                    memory_hints: match config.memory {
+                        GPUMemorySettings::prefer_performance => {
+                            wgpu::MemoryHints::Performance
+                        },
+                        GPUMemorySettings::prefer_memory => {
+                            wgpu::MemoryHints::MemoryUsage
+                        },
+                        GPUMemorySettings::custom_memory => {
+
+
+                            wgpu::MemoryHints::Manual{
+                                suballocated_device_memory_block_size : customize.gpu_memory_custom.to_rs_range(),
+                            }
+                        },
+                    },
+                },
+                None,
+                ))
+                    .map_err(|err| GPUComputingError::new(GPUComputingStatus::DeviceRequestFailed , format!("ERROR : could not allocate gpu resources which match your configs : {}" , err)))?;
+
+        // errors which are not caught by error scopes must not
+        // panic inside of the api , so they are only reported
+        device.on_uncaptured_error(Box::new(|err| {
+            set_last_error(&err.to_string());
+        }));
+
+        Ok(Context {
+            device : Arc::new(device) ,
+            queue : Arc::new(queue) ,
+        })
+    }
+
+    /// allocates gpu resources with the general config
+    /// which set_kernel_default_config uses
+    pub fn from_default_config() -> Result<Self , GPUComputingError> {
+        let config = GPUComputingConfig {
+            backend: GPUComputingBackend::opengl,
+            power: GPUPowerSettings::HighPerformance,
+            speed: GPUSpeedSettings::low_speed,
+            memory: GPUMemorySettings::prefer_memory,
+            gpu_index_in_backend_group : -1,
+            push_constants : false,
+            required_features : 0,
+            optional_features : 0,
+            device_selectors : std::ptr::null(),
+            device_selectors_len : 0,
+        };
+
+        Context::new(&config , &GPUCustomSettings::default())
+    }
+
+    /// limits which the gpu device is created with
+    pub fn limits(&self) -> GPUSpeedCustom {
+        GPUSpeedCustom::from(self.device.limits())
+    }
+
+    /// GPU_FEATURE bits of the features which are enabled on the
+    /// gpu device , see required_features and optional_features
+    /// of GPUComputingConfig
+    pub fn features(&self) -> u64 {
+        features_to_bits(self.device.features())
+    }
+
+    pub(crate) fn check_same_device(&self , device : &Arc<wgpu::Device>) -> Result<() , GPUComputingError> {
+        if Arc::ptr_eq(&self.device , device) {
+            Ok(())
+        } else {
+            Err(GPUComputingError::new(GPUComputingStatus::InvalidHandle , "ERROR : resource was created on another Context"))
+        }
+    }
+
+    /// runs kernel with workgroups number of workgroups in x , y and z
+    /// dimensions . every Binding is bound to its @group and @binding
+    /// of the kernel code , host data is uploaded and the results are
+    /// copied back to it based on the Binding type and Buffers stay on gpu side .
+    /// uniform data of all Bindings is uploaded together in one uniform buffer .
+    /// bindings are checked against the reflected layout of the kernel code ,
+    /// so missing ones or ones with too small data return InvalidArgument
+    pub fn compute(&self , kernel : &Kernel , workgroups : [u32; 3] , bindings : &mut [Binding<'_>]) -> Result<() , GPUComputingError> {
+        self.run(kernel , workgroups , bindings , None)
+    }
+
+    /// works like compute and sets push_constants to var<push_constant>
+    /// of the kernel code before dispatching , size of push_constants must
+    /// be multiple of 4 and it can not be bigger than var<push_constant>
+    pub fn compute_with_push_constants<T : Pod>(&self , kernel : &Kernel , workgroups : [u32; 3] , bindings : &mut [Binding<'_>] , push_constants : &[T]) -> Result<() , GPUComputingError> {
+        self.run(kernel , workgroups , bindings , Some(bytemuck::cast_slice(push_constants)))
+    }
+
+    /// works like compute but returns right after the task is submitted
+    /// to the gpu , bindings are moved into the returned Job and the
+    /// results are copied back when Job::poll or Job::wait finds out
+    /// that the task is finished
+    pub fn compute_async<'a>(&self , kernel : &Kernel , workgroups : [u32; 3] , bindings : Vec<Binding<'a>>) -> Result<Job<'a> , GPUComputingError> {
+        let submission = self.submit(&[DispatchInfo {
+            kernel ,
+            workgroups : Workgroups::Direct(workgroups) ,
+            bindings : &bindings ,
+            push_constants : None ,
+        }])?;
+        Ok(Job::new(bindings , submission))
+    }
+
+    /// compute_async with push constants , see compute_with_push_constants
+    pub fn compute_async_with_push_constants<'a , T : Pod>(&self , kernel : &Kernel , workgroups : [u32; 3] , bindings : Vec<Binding<'a>> , push_constants : &[T]) -> Result<Job<'a> , GPUComputingError> {
+        let submission = self.submit(&[DispatchInfo {
+            kernel ,
+            workgroups : Workgroups::Direct(workgroups) ,
+            bindings : &bindings ,
+            push_constants : Some(bytemuck::cast_slice(push_constants)) ,
+        }])?;
+        Ok(Job::new(bindings , submission))
+    }
+
+    fn run(&self , kernel : &Kernel , workgroups : [u32; 3] , bindings : &mut [Binding<'_>] , push_constants : Option<&[u8]>) -> Result<() , GPUComputingError> {
+        let submission = self.submit(&[DispatchInfo {
+            kernel ,
+            workgroups : Workgroups::Direct(workgroups) ,
+            bindings ,
+            push_constants ,
+        }])?;
+        submission.poll(true);
+        if !submission.is_done() {
+            return Err(GPUComputingError::new(GPUComputingStatus::MapFailed , "ERROR : gpu did not finish the task"));
+        }
+        submission.read_back(bindings.iter_mut())
+    }
+
+    // uploads host data , encodes every dispatch into one compute pass ,
+    // submits it and starts mapping staging buffers of the bindings
+    // which must be read back . staging buffers are indexed by position
+    // of the binding among bindings of all dispatches
+    pub(crate) fn submit(&self , dispatches : &[DispatchInfo<'_>]) -> Result<Submission , GPUComputingError> {
+        let device = &self.device;
+
+        let mut resources : Vec<Vec<BoundResource>> = Vec::with_capacity(dispatches.len());
+        for dispatch in dispatches {
+            self.check_dispatch(dispatch)?;
+            resources.push(self.bind_resources(dispatch.bindings)?);
+        }
+
+        // staging buffer of every binding which must be read back
+        let mut staging_buffers : Vec<(usize , wgpu::Buffer)> = Vec::new();
+
+        let index = with_error_scope(device , || {
+            let mut encoder =
+                device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+            {
+                let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                    label: None,
+                    timestamp_writes: None,
+                });
+
+                for (dispatch , resources) in dispatches.iter().zip(&resources) {
+                    let kernel = dispatch.kernel;
+                    let bindings = dispatch.bindings;
+
+                    // indices of bindings of each @group
+                    let mut groups : BTreeMap<u32 , Vec<usize>> = BTreeMap::new();
+                    for (i , binding) in bindings.iter().enumerate() {
+                        groups.entry(binding.group).or_default().push(i);
+                    }
+
+                    cpass.set_pipeline(&kernel.pipeline);
+
+                    for (group , indices) in &groups {
+                        let bind_group_layout = kernel.pipeline.get_bind_group_layout(*group);
+
+                        let entries : Vec<wgpu::BindGroupEntry> = indices.iter().map(|i| {
+                            wgpu::BindGroupEntry {
+                                binding: bindings[*i].bind,
+                                resource: resources[*i].as_binding_resource(),
+                            }
+                        }).collect();
+
+                        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                            label: None,
+                            layout: &bind_group_layout,
+                            entries: entries.as_slice() ,
+                        });
+
+                        cpass.set_bind_group(*group , &bind_group, &[]);
+                    }
+
+                    if let Some(push_constants) = dispatch.push_constants {
+                        cpass.set_push_constants(0 , push_constants);
+                    }
+
+                    cpass.insert_debug_marker("debug_marker");
+                    match &dispatch.workgroups {
+                        Workgroups::Direct(workgroups) => {
+                            cpass.dispatch_workgroups(workgroups[0], workgroups[1], workgroups[2]);
+                        },
+                        Workgroups::Indirect { buffer , offset , .. } => {
+                            cpass.dispatch_workgroups_indirect(buffer , *offset);
+                        },
+                    }
+                }
+            }
+
+
+            // only host data is read back , Buffers stay on
+            // gpu side and inputs do not change
+            let bindings = dispatches.iter().flat_map(|dispatch| dispatch.bindings);
+            let resources = resources.iter().flatten();
+            for (i , (binding , resource)) in bindings.zip(resources).enumerate() {
+                if binding.needs_read_back() {
+                    staging_buffers.push((i , resource.encode_read_back(device , &mut encoder)));
+                }
+            }
+
+            self.queue.submit(Some(encoder.finish()))
+        })?;
+
+        Ok(Submission::new(device , &self.queue , index , staging_buffers))
+    }
+
+    fn check_dispatch(&self , dispatch : &DispatchInfo<'_>) -> Result<() , GPUComputingError> {
+        let kernel = dispatch.kernel;
+        self.check_same_device(&kernel.device)?;
+
+        kernel.reflection.check_bindings(dispatch.bindings)?;
+
+        if let Workgroups::Indirect { buffer , device , offset } = &dispatch.workgroups {
+            self.check_same_device(device)?;
+            // x , y and z are three u32 values
+            if !offset.is_multiple_of(4) || offset.checked_add(12).is_none_or(|end| end > buffer.size()) {
+                return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : indirect offset {} must be multiple of 4 and 12 bytes of workgroup counts must fit in the buffer with size {}" , offset , buffer.size())));
+            }
+        }
+
+        if let Some(push_constants) = dispatch.push_constants {
+            if !self.device.features().contains(wgpu::Features::PUSH_CONSTANTS) {
+                return Err(GPUComputingError::new(GPUComputingStatus::UnsupportedFeature , "ERROR : push constants are not enabled on the gpu device , set push_constants field of GPUComputingConfig to true on a device which supports them"));
+            }
+            if kernel.push_constant_size == 0 {
+                return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : push constants provided but the kernel code has no var<push_constant>"));
+            }
+            if !(push_constants.len() as u32).is_multiple_of(wgpu::PUSH_CONSTANT_ALIGNMENT) || push_constants.len() > kernel.push_constant_size as usize {
+                return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : size of push constants is {} , it must be multiple of {} and not bigger than {} bytes of var<push_constant>" , push_constants.len() , wgpu::PUSH_CONSTANT_ALIGNMENT , kernel.push_constant_size)));
+            }
+        }
+
+        Ok(())
+    }
+
+    // creates gpu side resource of every binding and uploads host data
+    fn bind_resources(&self , bindings : &[Binding<'_>]) -> Result<Vec<BoundResource> , GPUComputingError> {
+        let device = &self.device;
+
+        // all of the uniform data is packed into one uniform buffer ,
+        // every part starts at multiple of min_uniform_buffer_offset_alignment
+        let uniform_alignment = device.limits().min_uniform_buffer_offset_alignment as u64;
+        let mut uniform_contents : Vec<u8> = Vec::new();
+        let mut uniform_ranges : Vec<(u64 , u64)> = Vec::new();
+
+        for binding in bindings.iter() {
+            if let BindingResource::Uniform { data } = &binding.resource {
+                if data.is_empty() {
+                    return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : uniform data of bind index {} of group {} is empty" , binding.bind , binding.group)));
+                }
+
+                let offset = (uniform_contents.len() as u64).next_multiple_of(uniform_alignment);
+                // uniform structs of wgsl are rounded up to 16 bytes
+                let size = (data.len() as u64).next_multiple_of(16);
+                uniform_contents.resize(offset as usize , 0);
+                uniform_contents.extend_from_slice(data);
+                uniform_contents.resize((offset + size) as usize , 0);
+                uniform_ranges.push((offset , size));
+            }
+        }
+
+        let uniform_buffer = if uniform_contents.is_empty() {
+            None
+        } else {
+            Some(Arc::new(with_error_scope(device , || {
+                device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Uniform Buffer"),
+                    contents: &uniform_contents ,
+                    usage: wgpu::BufferUsages::UNIFORM,
+                })
+            })?))
+        };
+        let mut uniform_ranges = uniform_ranges.into_iter();
+
+        let mut resources : Vec<BoundResource> = Vec::with_capacity(bindings.len());
+
+        for binding in bindings.iter() {
+            let resource = match &binding.resource {
+                BindingResource::Buffer { raw , device : buffer_device } => {
+                    self.check_same_device(buffer_device)?;
+                    BoundResource::Buffer {
+                        raw : Arc::clone(raw) ,
+                        range : None ,
+                    }
+                },
+                BindingResource::Uniform { .. } => {
+                    match (&uniform_buffer , uniform_ranges.next()) {
+                        (Some(uniform_buffer) , Some(range)) => BoundResource::Buffer {
+                            raw : Arc::clone(uniform_buffer) ,
+                            range : Some(range) ,
+                        } ,
+                        _ => return Err(GPUComputingError::new(GPUComputingStatus::InternalError , "ERROR : uniform buffer is not created")) ,
+                    }
+                },
+                BindingResource::Host { data , access } => {
+                    let storage_buffer = with_error_scope(device , || {
+                        match access {
+                            // outputs are not uploaded , new buffers are
+                            // zero initialized by wgpu
+                            GPUDataAccess::WriteOnly => {
+                                device.create_buffer(&wgpu::BufferDescriptor {
+                                    label: Some("Storage Buffer"),
+                                    size : align_to_copy(data.as_slice().len() as wgpu::BufferAddress) ,
+                                    usage: wgpu::BufferUsages::STORAGE
+                                        | wgpu::BufferUsages::COPY_SRC,
+                                    mapped_at_creation: false,
+                                })
+                            },
+                            GPUDataAccess::ReadOnly => {
+                                device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                                    label: Some("Storage Buffer"),
+                                    contents: data.as_slice() ,
+                                    usage: wgpu::BufferUsages::STORAGE,
+                                })
+                            },
+                            GPUDataAccess::ReadWrite => {
+                                device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                                    label: Some("Storage Buffer"),
+                                    contents: data.as_slice() ,
+                                    usage: wgpu::BufferUsages::STORAGE
+                                        | wgpu::BufferUsages::COPY_DST
+                                        | wgpu::BufferUsages::COPY_SRC,
+                                })
+                            },
+                        }
+                    })?;
+
+                    BoundResource::Buffer {
+                        raw : Arc::new(storage_buffer) ,
+                        range : None ,
+                    }
+                },
+                BindingResource::Texture { data , access , info , sampled } => {
+                    info.check(data.as_slice().len())?;
+
+                    let usage = if *sampled {
+                        wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST
+                    } else {
+                        wgpu::TextureUsages::STORAGE_BINDING
+                            | wgpu::TextureUsages::COPY_DST
+                            | wgpu::TextureUsages::COPY_SRC
+                    };
+
+                    let texture = with_error_scope(device , || {
+                        let texture = device.create_texture(&wgpu::TextureDescriptor {
+                            label: Some("Texture"),
+                            size: info.extent() ,
+                            mip_level_count: 1,
+                            sample_count: 1,
+                            dimension: info.wgpu_dimension() ,
+                            format: info.format.to_wgpu() ,
+                            usage ,
+                            view_formats: &[],
+                        });
+
+                        // outputs are not uploaded , new textures are
+                        // zero initialized by wgpu
+                        if *access != GPUDataAccess::WriteOnly {
+                            self.queue.write_texture(
+                                texture.as_image_copy() ,
+                                data.as_slice() ,
+                                wgpu::ImageDataLayout {
+                                    offset: 0,
+                                    bytes_per_row: Some(info.row_size()),
+                                    rows_per_image: Some(info.height),
+                                },
+                                info.extent() ,
+                            );
+                        }
+
+                        texture
+                    })?;
+
+                    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+                    BoundResource::Texture {
+                        texture ,
+                        view ,
+                        info : *info ,
+                    }
+                },
+                BindingResource::Sampler { info } => {
+                    BoundResource::Sampler(with_error_scope(device , || info.create_sampler(device))?)
+                },
+            };
+
+            resources.push(resource);
+        }
+
+        Ok(resources)
+    }
+}
+
+
+#[derive(Clone)]
+// number of workgroups of a dispatch
+pub(crate) enum Workgroups {
+    Direct([u32; 3]) ,
+    // x , y and z are read as u32 values from buffer at
+    // offset when the dispatch runs on gpu side
+    Indirect {
+        buffer : Arc<wgpu::Buffer> ,
+        device : Arc<wgpu::Device> ,
+        offset : u64 ,
+    } ,
+}
+
+// one dispatch_workgroups of a submission
+pub(crate) struct DispatchInfo<'d> {
+    pub(crate) kernel : &'d Kernel ,
+    pub(crate) workgroups : Workgroups ,
+    pub(crate) bindings : &'d [Binding<'d>] ,
+    pub(crate) push_constants : Option<&'d [u8]> ,
+}
+
+
+// gpu side resource of a Binding
+enum BoundResource {
+    Buffer {
+        raw : Arc<wgpu::Buffer> ,
+        // offset and size of the bound part , None binds entire buffer
+        range : Option<(u64 , u64)> ,
+    } ,
+    Texture {
+        texture : wgpu::Texture ,
+        view : wgpu::TextureView ,
+        info : GPUTextureInfo ,
+    } ,
+    Sampler(wgpu::Sampler) ,
+}
+
+impl BoundResource {
+    fn as_binding_resource(&self) -> wgpu::BindingResource<'_> {
+        match self {
+            BoundResource::Buffer { raw , range : None } => raw.as_entire_binding() ,
+            BoundResource::Buffer { raw , range : Some((offset , size)) } => {
+                wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer : raw ,
+                    offset : *offset ,
+                    size : wgpu::BufferSize::new(*size) ,
+                })
+            },
+            BoundResource::Texture { view , .. } => wgpu::BindingResource::TextureView(view) ,
+            BoundResource::Sampler(sampler) => wgpu::BindingResource::Sampler(sampler) ,
+        }
+    }
+
+    // copies the resource to new staging buffer , rows of
+    // textures are padded to COPY_BYTES_PER_ROW_ALIGNMENT
+    fn encode_read_back(&self , device : &wgpu::Device , encoder : &mut wgpu::CommandEncoder) -> wgpu::Buffer {
+        let size = match self {
+            BoundResource::Buffer { raw , .. } => raw.size() ,
+            BoundResource::Texture { info , .. } => info.padded_row_size() as u64 * info.rows() as u64 ,
+            BoundResource::Sampler(_) => 0 ,
+        };
+
+        let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None ,
+            size ,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        match self {
+            BoundResource::Buffer { raw , .. } => {
+                encoder.copy_buffer_to_buffer(raw , 0 , &staging_buffer , 0 , size);
+            },
+            BoundResource::Texture { texture , info , .. } => {
+                encoder.copy_texture_to_buffer(
+                    texture.as_image_copy() ,
+                    wgpu::ImageCopyBuffer {
+                        buffer: &staging_buffer ,
+                        layout: wgpu::ImageDataLayout {
+                            offset: 0,
+                            bytes_per_row: Some(info.padded_row_size()),
+                            rows_per_image: Some(info.height),
+                        },
+                    } ,
+                    info.extent() ,
+                );
+            },
+            BoundResource::Sampler(_) => {},
+        }
+
+        staging_buffer
+    }
+}
@@ -0,0 +1,199 @@
+use std::cell::RefCell;
+use std::ffi::CString;
+
+use crate::c_char;
+use crate::error::{GPUComputingError , GPUComputingStatus};
+
+
+#[derive(Clone , Debug , PartialEq , Eq)]
+/// one problem which naga found in kernel code , line and column
+/// start from 1 and they are 0 when the problem has no location
+/// in the source , like problems of SPIR-V modules
+pub struct CompileDiagnostic {
+    pub message : String ,
+    pub line : u32 ,
+    pub column : u32 ,
+    /// lines of the source which the problem points to
+    pub snippet : String ,
+}
+
+impl CompileDiagnostic {
+    pub(crate) fn new(message : impl Into<String> , span : naga::Span , source : Option<&str>) -> Self {
+        let mut diagnostic = CompileDiagnostic {
+            message : message.into() ,
+            line : 0 ,
+            column : 0 ,
+            snippet : String::new() ,
+        };
+
+        let Some(range) = span.to_range() else {
+            return diagnostic;
+        };
+        let Some(source) = source.filter(|source| range.end <= source.len()) else {
+            return diagnostic;
+        };
+
+        let location = span.location(source);
+        diagnostic.line = location.line_number;
+        diagnostic.column = location.line_position;
+
+        let start = source[..range.start].rfind('\n').map_or(0 , |pos| pos + 1);
+        let end = source[range.end..].find('\n').map_or(source.len() , |pos| range.end + pos);
+        diagnostic.snippet = source[start..end].to_string();
+
+        diagnostic
+    }
+}
+
+// message of err and the errors which caused it
+fn error_chain(err : &dyn std::error::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        message.push_str(" : ");
+        message.push_str(&err.to_string());
+        source = err.source();
+    }
+    message
+}
+
+fn compile_error(message : String , diagnostics : Vec<CompileDiagnostic>) -> GPUComputingError {
+    GPUComputingError::new(GPUComputingStatus::ShaderCompileError , message)
+        .with_diagnostics(diagnostics)
+}
+
+pub(crate) fn wgsl_error(err : &naga::front::wgsl::ParseError , code : &str) -> GPUComputingError {
+    let mut diagnostics : Vec<CompileDiagnostic> = err.labels()
+        .map(|(span , label)| CompileDiagnostic::new(label , span , Some(code)))
+        .collect();
+
+    // the first label points to the problem itself
+    match diagnostics.first_mut() {
+        Some(first) if first.message.is_empty() => first.message = err.message().to_string() ,
+        Some(first) => first.message = format!("{} , {}" , err.message() , first.message) ,
+        None => diagnostics.push(CompileDiagnostic::new(err.message() , naga::Span::default() , None)) ,
+    }
+
+    compile_error(format!("ERROR : could not parse the kernel code : {}" , err.emit_to_string(code)) , diagnostics)
+}
+
+pub(crate) fn glsl_error(err : &naga::front::glsl::ParseErrors , code : &str) -> GPUComputingError {
+    let diagnostics = err.errors.iter()
+        .map(|error| CompileDiagnostic::new(error.kind.to_string() , error.meta , Some(code)))
+        .collect();
+
+    compile_error(format!("ERROR : could not parse the GLSL code : {}" , err.emit_to_string(code)) , diagnostics)
+}
+
+pub(crate) fn spirv_error(err : &naga::front::spv::Error) -> GPUComputingError {
+    let diagnostics = vec![CompileDiagnostic::new(err.to_string() , naga::Span::default() , None)];
+
+    compile_error(format!("ERROR : could not parse the SPIR-V module : {}" , err) , diagnostics)
+}
+
+pub(crate) fn validation_error(err : &naga::WithSpan<naga::valid::ValidationError> , code : Option<&str>) -> GPUComputingError {
+    let message = error_chain(err.as_inner());
+
+    let mut diagnostics : Vec<CompileDiagnostic> = err.spans()
+        .map(|(span , label)| CompileDiagnostic::new(label.as_str() , *span , code))
+        .collect();
+
+    match diagnostics.first_mut() {
+        Some(first) => first.message = format!("{} , {}" , message , first.message) ,
+        None => diagnostics.push(CompileDiagnostic::new(message.as_str() , naga::Span::default() , None)) ,
+    }
+
+    let emitted = match code {
+        Some(code) => err.emit_to_string(code) ,
+        None => message ,
+    };
+
+    compile_error(format!("ERROR : could not validate the kernel code : {}" , emitted) , diagnostics)
+}
+
+pub(crate) fn entry_point_error(entry_point : &str) -> GPUComputingError {
+    let message = format!("kernel code has no compute entry point named {}" , entry_point);
+    let diagnostics = vec![CompileDiagnostic::new(message.as_str() , naga::Span::default() , None)];
+
+    compile_error(format!("ERROR : {}" , message) , diagnostics)
+}
+
+
+#[repr(C)]
+#[derive(Debug)]
+/// since v7.0.0 one problem which was found in kernel code ,
+/// line and column start from 1 and they are 0 when the problem
+/// has no location , snippet is the lines of the code which the
+/// problem points to
+pub struct GPUCompileDiagnostic {
+    pub message : *const c_char ,
+    pub line : u32 ,
+    pub column : u32 ,
+    pub snippet : *const c_char ,
+}
+
+// C side view of the diagnostics of the last failing call ,
+// the pointers of diagnostics point into strings
+#[derive(Default)]
+struct LastDiagnostics {
+    strings : Vec<CString> ,
+    diagnostics : Vec<GPUCompileDiagnostic> ,
+}
+
+thread_local! {
+    static LAST_DIAGNOSTICS : RefCell<LastDiagnostics> = RefCell::new(LastDiagnostics::default());
+}
+
+fn c_string(text : &str) -> CString {
+    CString::new(text.replace('\0' , " ")).unwrap_or_default()
+}
+
+pub(crate) fn set_last_diagnostics(diagnostics : &[CompileDiagnostic]) {
+    let mut last = LastDiagnostics::default();
+
+    for diagnostic in diagnostics {
+        let message = c_string(&diagnostic.message);
+        let snippet = c_string(&diagnostic.snippet);
+
+        // moving CString keeps its heap buffer in place
+        last.diagnostics.push(GPUCompileDiagnostic {
+            message : message.as_ptr() ,
+            line : diagnostic.line ,
+            column : diagnostic.column ,
+            snippet : snippet.as_ptr() ,
+        });
+        last.strings.push(message);
+        last.strings.push(snippet);
+    }
+
+    LAST_DIAGNOSTICS.with(|cell| *cell.borrow_mut() = last);
+}
+
+
+#[no_mangle]
+// SAFETY : diagnostics_len must be NULL or point to writable usize
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 when register_computing_kernel_code or other kernel
+/// registering function returns ShaderCompileError , this function
+/// returns array of the problems which were found in the code and writes
+/// its len to diagnostics_len . like emcompute_last_error it must be called
+/// from the same thread , the array is owned by the api and stays valid
+/// until the next failing call on that thread . NULL is returned when
+/// the last failing call has no diagnostics
+pub extern "C" fn emcompute_last_compile_diagnostics(diagnostics_len : *mut usize) -> *const GPUCompileDiagnostic {
+    LAST_DIAGNOSTICS.with(|cell| {
+        let last = cell.borrow();
+
+        if !diagnostics_len.is_null() {
+            unsafe {
+                *diagnostics_len = last.diagnostics.len();
+            }
+        }
+
+        if last.diagnostics.is_empty() {
+            std::ptr::null()
+        } else {
+            last.diagnostics.as_ptr()
+        }
+    })
+}
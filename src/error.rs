@@ -0,0 +1,166 @@
+use crate::c_char;
+use crate::diagnostic::{set_last_diagnostics , CompileDiagnostic};
+
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[repr(C)]
+#[derive(Clone , Copy , Debug , PartialEq , Eq)]
+/// since v7.0.0 every exported function of the api returns
+/// this status instead of panicking , when it is not Success
+/// you can get the description of the problem with emcompute_last_error
+pub enum GPUComputingStatus {
+    /// operation finished without any problem
+    Success = 0 ,
+    /// one of the passed arguments is NULL or has invalid value
+    InvalidArgument = 1 ,
+    /// passed EMContext , EMKernel , buffer_index or other handle
+    /// does not point to a living resource
+    InvalidHandle = 2 ,
+    /// no gpu device matched the passed GPUComputingConfig
+    AdapterNotFound = 3 ,
+    /// gpu device exists but it could not provide the requested
+    /// limits or features
+    DeviceRequestFailed = 4 ,
+    /// kernel code could not be parsed , validated or compiled
+    ShaderCompileError = 5 ,
+    /// gpu side rejected the task , for example because of
+    /// mismatched bindings
+    ValidationError = 6 ,
+    /// reading the results back from gpu side failed
+    MapFailed = 7 ,
+    /// gpu side could not allocate the needed memory
+    OutOfMemory = 8 ,
+    /// unexpected problem inside of the api , please report it
+    InternalError = 9 ,
+    /// gpu device does not support the feature which
+    /// is needed for the operation
+    UnsupportedFeature = 10 ,
+    /// computing task of EMJob is not finished yet , it is
+    /// not an error and emcompute_last_error is not changed
+    JobPending = 11 ,
+}
+
+#[derive(Clone , Debug)]
+/// error which contains the GPUComputingStatus and
+/// the message describing it , errors of kernel codes
+/// which naga rejected contain its diagnostics too
+pub struct GPUComputingError {
+    pub status : GPUComputingStatus ,
+    pub message : String ,
+    pub diagnostics : Vec<CompileDiagnostic> ,
+}
+
+impl GPUComputingError {
+    pub fn new(status : GPUComputingStatus , message : impl Into<String>) -> Self {
+        GPUComputingError {
+            status ,
+            message : message.into() ,
+            diagnostics : Vec::new() ,
+        }
+    }
+
+    pub(crate) fn with_diagnostics(mut self , diagnostics : Vec<CompileDiagnostic>) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+}
+
+impl std::fmt::Display for GPUComputingError {
+    fn fmt(&self , f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f , "{:?} : {}" , self.status , self.message)
+    }
+}
+
+impl std::error::Error for GPUComputingError {}
+
+impl From<wgpu::Error> for GPUComputingError {
+    fn from(err : wgpu::Error) -> Self {
+        match err {
+            wgpu::Error::OutOfMemory { .. } => {
+                GPUComputingError::new(GPUComputingStatus::OutOfMemory , err.to_string())
+            },
+            wgpu::Error::Validation { .. } => {
+                GPUComputingError::new(GPUComputingStatus::ValidationError , err.to_string())
+            },
+            wgpu::Error::Internal { .. } => {
+                GPUComputingError::new(GPUComputingStatus::InternalError , err.to_string())
+            },
+        }
+    }
+}
+
+/// runs body while out of memory and validation errors of the device
+/// are captured , so they are returned instead of reaching the
+/// uncaptured error handler
+pub fn with_error_scope<T , F>(device : &wgpu::Device , body : F) -> Result<T , GPUComputingError>
+where F : FnOnce() -> T {
+    device.push_error_scope(wgpu::ErrorFilter::OutOfMemory);
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+    let result = body();
+
+    let validation_error = pollster::block_on(device.pop_error_scope());
+    let memory_error = pollster::block_on(device.pop_error_scope());
+
+    if let Some(err) = memory_error {
+        return Err(err.into());
+    }
+    if let Some(err) = validation_error {
+        return Err(err.into());
+    }
+
+    Ok(result)
+}
+
+thread_local! {
+    static LAST_ERROR : RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+pub fn set_last_error(message : &str) {
+    // interior NUL bytes would make CString::new fail
+    let message = CString::new(message.replace('\0' , " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// runs body of an exported function , converts its result to
+/// GPUComputingStatus and keeps panics from unwinding into C side
+pub fn ffi_call<F>(body : F) -> GPUComputingStatus
+where F : FnOnce() -> Result<() , GPUComputingError> {
+    match catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => GPUComputingStatus::Success ,
+        Ok(Err(err)) => {
+            set_last_error(&err.message);
+            set_last_diagnostics(&err.diagnostics);
+            err.status
+        },
+        Err(payload) => {
+            let message = if let Some(msg) = payload.downcast_ref::<&str>() {
+                msg.to_string()
+            } else if let Some(msg) = payload.downcast_ref::<String>() {
+                msg.clone()
+            } else {
+                "unknown panic".to_string()
+            };
+            set_last_error(&format!("ERROR : internal panic inside of the api : {}" , message));
+            set_last_diagnostics(&[]);
+            GPUComputingStatus::InternalError
+        },
+    }
+}
+
+#[no_mangle]
+/// since v7.0.0 when a function returns anything other than Success
+/// you can call this function from the same thread to get the
+/// message describing the problem . returned string is owned by
+/// the api and stays valid until the next failing call on that thread ,
+/// NULL is returned when nothing failed yet
+pub extern "C" fn emcompute_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        match &*last.borrow() {
+            Some(message) => message.as_ptr(),
+            None => std::ptr::null(),
+        }
+    })
+}
@@ -0,0 +1,184 @@
+// since v7.0.0 bits of features fields , they are stable between
+// versions of the api , unlike bits of wgpu::Features
+
+/// f16 type in kernel codes
+pub const GPU_FEATURE_SHADER_F16 : u64 = 1 << 0;
+/// f64 type in kernel codes
+pub const GPU_FEATURE_SHADER_F64 : u64 = 1 << 1;
+/// i64 and u64 types in kernel codes
+pub const GPU_FEATURE_SHADER_INT64 : u64 = 1 << 2;
+/// atomicMin and atomicMax of 64 bit integers in storage buffers
+pub const GPU_FEATURE_SHADER_INT64_ATOMIC_MIN_MAX : u64 = 1 << 3;
+/// every atomic operation of 64 bit integers
+pub const GPU_FEATURE_SHADER_INT64_ATOMIC_ALL_OPS : u64 = 1 << 4;
+/// subgroup operations in compute kernel codes
+pub const GPU_FEATURE_SUBGROUP : u64 = 1 << 5;
+/// subgroupBarrier in kernel codes
+pub const GPU_FEATURE_SUBGROUP_BARRIER : u64 = 1 << 6;
+/// timestamp queries at the start and end of passes
+pub const GPU_FEATURE_TIMESTAMP_QUERY : u64 = 1 << 7;
+/// timestamp queries inside of command encoders
+pub const GPU_FEATURE_TIMESTAMP_QUERY_INSIDE_ENCODERS : u64 = 1 << 8;
+/// timestamp queries inside of compute passes
+pub const GPU_FEATURE_TIMESTAMP_QUERY_INSIDE_PASSES : u64 = 1 << 9;
+/// pipeline statistics queries
+pub const GPU_FEATURE_PIPELINE_STATISTICS_QUERY : u64 = 1 << 10;
+/// var<push_constant> in kernel codes
+pub const GPU_FEATURE_PUSH_CONSTANTS : u64 = 1 << 11;
+/// buffers which can be mapped and used by kernel codes at the same
+/// time , it is fast on integrated gpus but slow on discrete ones
+pub const GPU_FEATURE_MAPPABLE_PRIMARY_BUFFERS : u64 = 1 << 12;
+/// arrays of buffer bindings
+pub const GPU_FEATURE_BUFFER_BINDING_ARRAY : u64 = 1 << 13;
+/// arrays of storage buffer and storage texture bindings
+pub const GPU_FEATURE_STORAGE_RESOURCE_BINDING_ARRAY : u64 = 1 << 14;
+/// arrays of texture bindings
+pub const GPU_FEATURE_TEXTURE_BINDING_ARRAY : u64 = 1 << 15;
+/// texture formats have the usages which the adapter supports
+/// instead of the ones which WebGPU guarantees
+pub const GPU_FEATURE_TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES : u64 = 1 << 16;
+/// filtering samplers on R32Float , Rg32Float and Rgba32Float textures
+pub const GPU_FEATURE_FLOAT32_FILTERABLE : u64 = 1 << 17;
+/// 16 bit normalized texture formats
+pub const GPU_FEATURE_TEXTURE_FORMAT_16BIT_NORM : u64 = 1 << 18;
+
+const FEATURES : [(u64 , wgpu::Features); 19] = [
+    (GPU_FEATURE_SHADER_F16 , wgpu::Features::SHADER_F16) ,
+    (GPU_FEATURE_SHADER_F64 , wgpu::Features::SHADER_F64) ,
+    (GPU_FEATURE_SHADER_INT64 , wgpu::Features::SHADER_INT64) ,
+    (GPU_FEATURE_SHADER_INT64_ATOMIC_MIN_MAX , wgpu::Features::SHADER_INT64_ATOMIC_MIN_MAX) ,
+    (GPU_FEATURE_SHADER_INT64_ATOMIC_ALL_OPS , wgpu::Features::SHADER_INT64_ATOMIC_ALL_OPS) ,
+    (GPU_FEATURE_SUBGROUP , wgpu::Features::SUBGROUP) ,
+    (GPU_FEATURE_SUBGROUP_BARRIER , wgpu::Features::SUBGROUP_BARRIER) ,
+    (GPU_FEATURE_TIMESTAMP_QUERY , wgpu::Features::TIMESTAMP_QUERY) ,
+    (GPU_FEATURE_TIMESTAMP_QUERY_INSIDE_ENCODERS , wgpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS) ,
+    (GPU_FEATURE_TIMESTAMP_QUERY_INSIDE_PASSES , wgpu::Features::TIMESTAMP_QUERY_INSIDE_PASSES) ,
+    (GPU_FEATURE_PIPELINE_STATISTICS_QUERY , wgpu::Features::PIPELINE_STATISTICS_QUERY) ,
+    (GPU_FEATURE_PUSH_CONSTANTS , wgpu::Features::PUSH_CONSTANTS) ,
+    (GPU_FEATURE_MAPPABLE_PRIMARY_BUFFERS , wgpu::Features::MAPPABLE_PRIMARY_BUFFERS) ,
+    (GPU_FEATURE_BUFFER_BINDING_ARRAY , wgpu::Features::BUFFER_BINDING_ARRAY) ,
+    (GPU_FEATURE_STORAGE_RESOURCE_BINDING_ARRAY , wgpu::Features::STORAGE_RESOURCE_BINDING_ARRAY) ,
+    (GPU_FEATURE_TEXTURE_BINDING_ARRAY , wgpu::Features::TEXTURE_BINDING_ARRAY) ,
+    (GPU_FEATURE_TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES , wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) ,
+    (GPU_FEATURE_FLOAT32_FILTERABLE , wgpu::Features::FLOAT32_FILTERABLE) ,
+    (GPU_FEATURE_TEXTURE_FORMAT_16BIT_NORM , wgpu::Features::TEXTURE_FORMAT_16BIT_NORM) ,
+];
+
+/// GPU_FEATURE bits of features , features which have
+/// no bit are left out
+pub(crate) fn features_to_bits(features : wgpu::Features) -> u64 {
+    FEATURES.iter()
+        .filter(|(_ , feature)| features.contains(*feature))
+        .fold(0 , |bits , (bit , _)| bits | bit)
+}
+
+/// wgpu::Features of GPU_FEATURE bits , unknown bits are ignored
+pub(crate) fn bits_to_features(bits : u64) -> wgpu::Features {
+    FEATURES.iter()
+        .filter(|(bit , _)| bits & bit != 0)
+        .fold(wgpu::Features::empty() , |features , (_ , feature)| features | *feature)
+}
+
+/// bits of bits which are not GPU_FEATURE bits
+pub(crate) fn unknown_feature_bits(bits : u64) -> u64 {
+    FEATURES.iter().fold(bits , |bits , (bit , _)| bits & !bit)
+}
+
+
+/// kernel codes can run on the device , it is false on WebGL
+pub const GPU_DOWNLEVEL_COMPUTE_SHADERS : u32 = 1 << 0;
+/// indirect dispatches are supported
+pub const GPU_DOWNLEVEL_INDIRECT_EXECUTION : u32 = 1 << 1;
+/// buffer bindings can have sizes which are not multiple of 16
+pub const GPU_DOWNLEVEL_BUFFER_BINDINGS_NOT_16_BYTE_ALIGNED : u32 = 1 << 2;
+/// textures which are not power of two sized can have mipmaps
+pub const GPU_DOWNLEVEL_NON_POWER_OF_TWO_MIPMAPPED_TEXTURES : u32 = 1 << 3;
+/// cube array textures are supported
+pub const GPU_DOWNLEVEL_CUBE_ARRAY_TEXTURES : u32 = 1 << 4;
+/// comparison samplers are supported
+pub const GPU_DOWNLEVEL_COMPARISON_SAMPLERS : u32 = 1 << 5;
+/// anisotropic filtering of samplers is supported
+pub const GPU_DOWNLEVEL_ANISOTROPIC_FILTERING : u32 = 1 << 6;
+/// depth textures can be copied to and from buffers
+pub const GPU_DOWNLEVEL_DEPTH_TEXTURE_AND_BUFFER_COPIES : u32 = 1 << 7;
+/// every texture format usage which WebGPU guarantees is supported
+pub const GPU_DOWNLEVEL_WEBGPU_TEXTURE_FORMAT_SUPPORT : u32 = 1 << 8;
+/// resolving queries does not block the host
+pub const GPU_DOWNLEVEL_NONBLOCKING_QUERY_RESOLVE : u32 = 1 << 9;
+
+const DOWNLEVEL_FLAGS : [(u32 , wgpu::DownlevelFlags); 10] = [
+    (GPU_DOWNLEVEL_COMPUTE_SHADERS , wgpu::DownlevelFlags::COMPUTE_SHADERS) ,
+    (GPU_DOWNLEVEL_INDIRECT_EXECUTION , wgpu::DownlevelFlags::INDIRECT_EXECUTION) ,
+    (GPU_DOWNLEVEL_BUFFER_BINDINGS_NOT_16_BYTE_ALIGNED , wgpu::DownlevelFlags::BUFFER_BINDINGS_NOT_16_BYTE_ALIGNED) ,
+    (GPU_DOWNLEVEL_NON_POWER_OF_TWO_MIPMAPPED_TEXTURES , wgpu::DownlevelFlags::NON_POWER_OF_TWO_MIPMAPPED_TEXTURES) ,
+    (GPU_DOWNLEVEL_CUBE_ARRAY_TEXTURES , wgpu::DownlevelFlags::CUBE_ARRAY_TEXTURES) ,
+    (GPU_DOWNLEVEL_COMPARISON_SAMPLERS , wgpu::DownlevelFlags::COMPARISON_SAMPLERS) ,
+    (GPU_DOWNLEVEL_ANISOTROPIC_FILTERING , wgpu::DownlevelFlags::ANISOTROPIC_FILTERING) ,
+    (GPU_DOWNLEVEL_DEPTH_TEXTURE_AND_BUFFER_COPIES , wgpu::DownlevelFlags::DEPTH_TEXTURE_AND_BUFFER_COPIES) ,
+    (GPU_DOWNLEVEL_WEBGPU_TEXTURE_FORMAT_SUPPORT , wgpu::DownlevelFlags::WEBGPU_TEXTURE_FORMAT_SUPPORT) ,
+    (GPU_DOWNLEVEL_NONBLOCKING_QUERY_RESOLVE , wgpu::DownlevelFlags::NONBLOCKING_QUERY_RESOLVE) ,
+];
+
+#[repr(C)]
+#[derive(Clone , Copy , Debug , PartialEq , Eq)]
+/// since v7.0.0 collections of shader features which the
+/// device supports , named after shader models of D3D
+pub enum GPUShaderModel {
+    /// very limited shaders
+    Sm2 = 0 ,
+    /// no storage textures
+    Sm4 = 1 ,
+    /// everything which WebGPU needs
+    Sm5 = 2 ,
+}
+
+#[repr(C)]
+#[derive(Clone , Copy , Debug)]
+/// since v7.0.0 what the device supports of the features which
+/// WebGPU guarantees , older backends like OpenGL ES may miss some
+pub struct GPUDownlevelCapabilities {
+    /// GPU_DOWNLEVEL bits of the supported capabilities
+    pub flags : u32 ,
+    pub shader_model : GPUShaderModel ,
+    /// true when the device supports everything which WebGPU needs
+    pub webgpu_compliant : bool ,
+}
+
+impl From<wgpu::DownlevelCapabilities> for GPUDownlevelCapabilities {
+    fn from(capabilities : wgpu::DownlevelCapabilities) -> Self {
+        GPUDownlevelCapabilities {
+            flags : DOWNLEVEL_FLAGS.iter()
+                .filter(|(_ , flag)| capabilities.flags.contains(*flag))
+                .fold(0 , |bits , (bit , _)| bits | bit) ,
+            shader_model : match capabilities.shader_model {
+                wgpu::ShaderModel::Sm2 => GPUShaderModel::Sm2 ,
+                wgpu::ShaderModel::Sm4 => GPUShaderModel::Sm4 ,
+                wgpu::ShaderModel::Sm5 => GPUShaderModel::Sm5 ,
+            } ,
+            webgpu_compliant : capabilities.is_webgpu_compliant() ,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_bits_are_found() {
+        let known = FEATURES.iter().fold(0 , |bits , (bit , _)| bits | bit);
+
+        assert_eq!(unknown_feature_bits(known) , 0);
+        assert_eq!(unknown_feature_bits(GPU_FEATURE_SHADER_F16 | 1 << 40) , 1 << 40);
+        assert_eq!(unknown_feature_bits(!known) , !known);
+    }
+
+    #[test]
+    fn known_bits_round_trip() {
+        for (bit , feature) in FEATURES {
+            assert_eq!(bits_to_features(bit) , feature);
+            assert_eq!(features_to_bits(feature) , bit);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{with_gpu_collection , EMContext};
+use crate::buffer::{align_to_copy , Buffer};
+use crate::error::{ffi_call , GPUComputingError , GPUComputingStatus};
+
+
+fn to_usize(value : u64) -> Result<usize , GPUComputingError> {
+    usize::try_from(value)
+        .map_err(|_| GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : {} is too big for this platform" , value)))
+}
+
+/// returns computing buffer with buffer_index which lives on context
+pub(crate) fn get_computing_buffer(context : *mut EMContext , buffer_index : usize) -> Result<Arc<Mutex<Buffer<u8>>> , GPUComputingError> {
+    with_gpu_collection(context , |collection| {
+        match collection.buffers.get(buffer_index) {
+            Some(Some(buffer)) => Ok(Arc::clone(buffer)),
+            _ => Err(GPUComputingError::new(GPUComputingStatus::InvalidHandle , format!("ERROR : buffer_index {} does not point to a living computing buffer" , buffer_index))),
+        }
+    })
+}
+
+
+#[no_mangle]
+// SAFETY : buffer_index must point to writable usize
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 buffers can be kept on gpu side between compute calls .
+/// this function creates zero initialized buffer with size bytes
+/// (rounded up to multiple of 4) on gpu resources of context
+/// and writes its index to buffer_index .
+/// for binding it set buffer_index field of DataBinder to this value
+pub extern "C" fn create_computing_buffer(context : *mut EMContext , size : u64 , buffer_index : *mut usize) -> GPUComputingStatus {
+    ffi_call(|| {
+        if buffer_index.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL buffer_index provided for create_computing_buffer"));
+        }
+        if size == 0 {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : size of computing buffer can not be 0"));
+        }
+
+        let real_context = with_gpu_collection(context , |collection| Ok(collection.context.clone()))?;
+
+        let buffer = Buffer::<u8>::new(&real_context , to_usize(align_to_copy(size))?)?;
+
+        let index = with_gpu_collection(context , |collection| {
+            collection.buffers.push(Some(Arc::new(Mutex::new(buffer))));
+            Ok(collection.buffers.len() - 1)
+        })?;
+
+        unsafe {
+            *buffer_index = index;
+        }
+
+        Ok(())
+    })
+}
+
+#[no_mangle]
+// SAFETY : data must point to data_len readable bytes
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// writes data_len bytes of data to the computing buffer starting
+/// from offset , offset and data_len must be multiples of 4
+pub extern "C" fn write_computing_buffer(context : *mut EMContext , buffer_index : usize , offset : u64 , data : *const u8 , data_len : usize) -> GPUComputingStatus {
+    ffi_call(|| {
+        if data.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL data provided for write_computing_buffer"));
+        }
+
+        let buffer = get_computing_buffer(context , buffer_index)?;
+
+        let data : &[u8] = unsafe {
+            std::slice::from_raw_parts(data , data_len)
+        };
+
+        let buffer = buffer.lock().unwrap();
+        buffer.write(to_usize(offset)? , data)
+    })
+}
+
+#[no_mangle]
+// SAFETY : data must point to data_len writable bytes
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// reads data_len bytes of the computing buffer starting from offset
+/// to the memory which data points to , offset and data_len must be
+/// multiples of 4
+pub extern "C" fn read_computing_buffer(context : *mut EMContext , buffer_index : usize , offset : u64 , data : *mut u8 , data_len : usize) -> GPUComputingStatus {
+    ffi_call(|| {
+        if data.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL data provided for read_computing_buffer"));
+        }
+
+        let buffer = get_computing_buffer(context , buffer_index)?;
+
+        let out : &mut [u8] = unsafe {
+            std::slice::from_raw_parts_mut(data , data_len)
+        };
+
+        let buffer = buffer.lock().unwrap();
+        buffer.read_into(to_usize(offset)? , out)
+    })
+}
+
+#[no_mangle]
+/// changes size of the computing buffer to new_size bytes (rounded up
+/// to multiple of 4) , the old content is kept as much as it fits
+/// and the new part is zero initialized . buffer_index stays the same
+pub extern "C" fn resize_computing_buffer(context : *mut EMContext , buffer_index : usize , new_size : u64) -> GPUComputingStatus {
+    ffi_call(|| {
+        if new_size == 0 {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : size of computing buffer can not be 0"));
+        }
+
+        let buffer = get_computing_buffer(context , buffer_index)?;
+
+        let mut buffer = buffer.lock().unwrap();
+        buffer.resize(to_usize(align_to_copy(new_size))?)
+    })
+}
+
+#[no_mangle]
+/// frees the computing buffer , its buffer_index can not
+/// be used anymore
+pub extern "C" fn free_computing_buffer(context : *mut EMContext , buffer_index : usize) -> GPUComputingStatus {
+    ffi_call(|| {
+        with_gpu_collection(context , |collection| {
+            match collection.buffers.get_mut(buffer_index) {
+                Some(slot @ Some(_)) => {
+                    *slot = None;
+                    Ok(())
+                },
+                _ => Err(GPUComputingError::new(GPUComputingStatus::InvalidHandle , format!("ERROR : buffer_index {} does not point to a living computing buffer" , buffer_index))),
+            }
+        })
+    })
+}
@@ -0,0 +1,203 @@
+use std::ffi::c_void;
+use std::sync::{Arc , Mutex};
+
+use crate::{from_ptr , to_ptr , translate_groups , with_gpu_collection , CKernel , ComputeTask , EMCommandList , EMContext , EMJob , GroupOfBinders , HandleMap , NewAllocations};
+use crate::command_list::CommandList;
+use crate::context::{Context , Workgroups};
+use crate::error::{ffi_call , GPUComputingError , GPUComputingStatus};
+use crate::gpu_buffer::get_computing_buffer;
+use crate::gpu_job::{add_gpu_job , GPUJob , GPUJobCallback};
+
+
+// recorded dispatches which C side reaches by EMCommandList
+// handle , context is the handle which they run on
+pub(crate) struct GPUCommandList {
+    list : CommandList<'static> ,
+    gpu_context : Context ,
+    new_allocations : NewAllocations ,
+    pub(crate) context : usize ,
+}
+
+pub(crate) static GPU_COMMAND_LISTS : Mutex<HandleMap<GPUCommandList>> = Mutex::new(HandleMap::new());
+
+fn with_command_list<T , F>(list : *mut EMCommandList , body : F) -> Result<T , GPUComputingError>
+where F : FnOnce(&mut GPUCommandList) -> Result<T , GPUComputingError> {
+    let mut lists = GPU_COMMAND_LISTS.lock().unwrap();
+    match lists.get_mut(from_ptr(list)) {
+        None => Err(GPUComputingError::new(GPUComputingStatus::InvalidHandle , "ERROR : invalid or destroyed EMCommandList provided , please use the handle which you received from emcompute_command_list_create function")),
+        Some(command_list) => body(command_list),
+    }
+}
+
+// indirect is buffer_index and byte offset of the computing
+// buffer which workgroup counts are read from
+fn record(list : *mut EMCommandList , kernel : *mut CKernel , data_for_gpu : *mut GroupOfBinders , gpu_data_len : usize , push_constants : Option<&[u8]> , indirect : Option<(usize , u64)>) -> Result<() , GPUComputingError> {
+    let ComputeTask { context_handle , kernel , workgroups , bindings , new_allocations , .. } = translate_groups(kernel , data_for_gpu , gpu_data_len)?;
+
+    let workgroups = match indirect {
+        None => Workgroups::Direct(workgroups) ,
+        Some((buffer_index , offset)) => {
+            let buffer = get_computing_buffer(to_ptr(context_handle) , buffer_index)?;
+            let buffer = buffer.lock().unwrap();
+            Workgroups::Indirect {
+                buffer : Arc::clone(&buffer.raw) ,
+                device : Arc::clone(&buffer.context.device) ,
+                offset ,
+            }
+        },
+    };
+
+    with_command_list(list , |command_list| {
+        if command_list.context != context_handle {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : context field of CKernel is not the EMContext which the command list is created on"));
+        }
+
+        command_list.list.record(&kernel , workgroups , bindings , push_constants.map(<[u8]>::to_vec))?;
+        command_list.new_allocations.append(new_allocations);
+
+        Ok(())
+    })
+}
+
+// takes recorded dispatches out , so the command list
+// can record new ones while they run
+fn take_recorded(list : *mut EMCommandList) -> Result<(CommandList<'static> , NewAllocations , usize) , GPUComputingError> {
+    with_command_list(list , |command_list| {
+        let recorded = std::mem::replace(&mut command_list.list , CommandList::new(&command_list.gpu_context));
+        let new_allocations = std::mem::replace(&mut command_list.new_allocations , NewAllocations(Vec::new()));
+        Ok((recorded , new_allocations , command_list.context))
+    })
+}
+
+
+#[no_mangle]
+// SAFETY : list must point to writable EMCommandList pointer
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 creates empty command list on context and writes
+/// its handle to list . command lists record several dispatches ,
+/// possibly of different kernel codes , and send all of them to the
+/// gpu in one submission , so the stages of a pipeline of kernels do
+/// not pay for uploading , submitting and reading back one by one
+pub extern "C" fn emcompute_command_list_create(context : *mut EMContext , list : *mut *mut EMCommandList) -> GPUComputingStatus {
+    ffi_call(|| {
+        if list.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL list provided for emcompute_command_list_create"));
+        }
+
+        let gpu_context = with_gpu_collection(context , |collection| Ok(collection.context.clone()))?;
+
+        let handle = GPU_COMMAND_LISTS.lock().unwrap()
+            .insert(GPUCommandList {
+                list : CommandList::new(&gpu_context) ,
+                gpu_context ,
+                new_allocations : NewAllocations(Vec::new()) ,
+                context : from_ptr(context) ,
+            })
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::OutOfMemory , "ERROR : too many EMCommandList handles are alive"))?;
+
+        unsafe {
+            *list = to_ptr(handle);
+        }
+
+        Ok(())
+    })
+}
+
+#[no_mangle]
+/// since v7.0.0 records kernel with data_for_gpu like compute function
+/// does , but nothing is sent to the gpu until the list is run .
+/// dispatches run in the order they are recorded and each of them sees
+/// what the previous ones wrote to computing buffers , so data which
+/// flows between stages must be bound with buffer_index and it never
+/// leaves gpu side . host data of DataBinders is uploaded before the
+/// first dispatch and read back after the last one , so it must stay
+/// valid until the list is finished and it is not shared between dispatches
+pub extern "C" fn emcompute_command_list_dispatch(list : *mut EMCommandList , kernel : *mut CKernel , data_for_gpu : *mut GroupOfBinders , gpu_data_len : usize) -> GPUComputingStatus {
+    ffi_call(|| record(list , kernel , data_for_gpu , gpu_data_len , None , None))
+}
+
+#[no_mangle]
+// SAFETY : kernel must point to CKernel , data_for_gpu to gpu_data_len GroupOfBinders whose DataBinders stay valid until the list is run and push_constants to push_constants_len readable bytes
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 records kernel like emcompute_command_list_dispatch and
+/// sets push_constants_len bytes of push_constants to var<push_constant>
+/// of the kernel code before dispatching it , see compute_with_push_constants
+pub extern "C" fn emcompute_command_list_dispatch_with_push_constants(list : *mut EMCommandList , kernel : *mut CKernel , data_for_gpu : *mut GroupOfBinders , gpu_data_len : usize , push_constants : *const u8 , push_constants_len : usize) -> GPUComputingStatus {
+    ffi_call(|| {
+        if push_constants.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : push_constants arg of emcompute_command_list_dispatch_with_push_constants function is NULL"));
+        }
+
+        let push_constants : &[u8] = unsafe {
+            std::slice::from_raw_parts(push_constants , push_constants_len)
+        };
+
+        record(list , kernel , data_for_gpu , gpu_data_len , Some(push_constants) , None)
+    })
+}
+
+#[no_mangle]
+/// since v7.0.0 records kernel like emcompute_command_list_dispatch but
+/// x , y and z fields of kernel are ignored and number of workgroups is
+/// read as three uint32_t values from the computing buffer with
+/// indirect_buffer_index starting at byte indirect_offset when the dispatch
+/// runs , so an earlier dispatch of the list can write them on gpu side
+/// without reading anything back . indirect_offset must be multiple of 4
+pub extern "C" fn emcompute_command_list_dispatch_indirect(list : *mut EMCommandList , kernel : *mut CKernel , data_for_gpu : *mut GroupOfBinders , gpu_data_len : usize , indirect_buffer_index : usize , indirect_offset : u64) -> GPUComputingStatus {
+    ffi_call(|| record(list , kernel , data_for_gpu , gpu_data_len , None , Some((indirect_buffer_index , indirect_offset))))
+}
+
+#[no_mangle]
+/// since v7.0.0 sends every recorded dispatch of list to the gpu in one
+/// submission and blocks until the results are copied back . after it
+/// returns the list is empty and new dispatches can be recorded on it
+pub extern "C" fn emcompute_command_list_run(list : *mut EMCommandList) -> GPUComputingStatus {
+    ffi_call(|| {
+        let (recorded , new_allocations , _) = take_recorded(list)?;
+
+        let result = recorded.run();
+        new_allocations.finish(result.is_ok());
+
+        result
+    })
+}
+
+#[no_mangle]
+// SAFETY : job must point to writable EMJob pointer
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 works like emcompute_command_list_run but returns right
+/// after the dispatches are submitted and writes handle of the task to
+/// job , see compute_async for callback , user_data and the handle
+pub extern "C" fn emcompute_command_list_run_async(list : *mut EMCommandList , callback : GPUJobCallback , user_data : *mut c_void , job : *mut *mut EMJob) -> GPUComputingStatus {
+    ffi_call(|| {
+        if job.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL job provided for emcompute_command_list_run_async"));
+        }
+
+        let (recorded , new_allocations , context) = take_recorded(list)?;
+
+        let handle = add_gpu_job(context , GPUJob {
+            job : recorded.submit()? ,
+            new_allocations : Some(new_allocations) ,
+        } , callback , user_data)?;
+
+        unsafe {
+            *job = handle;
+        }
+
+        Ok(())
+    })
+}
+
+#[no_mangle]
+/// since v7.0.0 frees list and the dispatches which are recorded on it
+/// but not run yet , the handle can not be used anymore
+pub extern "C" fn emcompute_command_list_destroy(list : *mut EMCommandList) -> GPUComputingStatus {
+    ffi_call(|| {
+        GPU_COMMAND_LISTS.lock().unwrap()
+            .remove(from_ptr(list))
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidHandle , "ERROR : invalid or already destroyed EMCommandList provided for emcompute_command_list_destroy"))?;
+
+        Ok(())
+    })
+}
@@ -1,16 +1,21 @@
 use crate::c_char;
+use crate::util::cchar_as_string;
+
+use crate::error::{ffi_call , GPUComputingError , GPUComputingStatus};
+use crate::features::{features_to_bits , GPUDownlevelCapabilities};
 
 use crate::
 {GPUComputingBackend , 
     GPUPowerSettings , 
     GPUSpeedSettings , 
     GPUMemorySettings , 
-    GPUComputingConfig};
+    GPUComputingConfig ,
+    GPUSpeedCustom};
 
 
 
 #[repr(C)]
-#[derive(Clone , Debug)]
+#[derive(Clone , Copy , Debug , PartialEq , Eq)]
 /// Computing devices types
 pub enum GPUDeviceType {
     Other = 0,
@@ -32,6 +37,96 @@ impl From<wgpu::DeviceType> for GPUDeviceType {
     }
 }
 
+// lower rank is picked first when several devices
+// match the same GPUDeviceSelector
+fn device_type_rank(device_type : wgpu::DeviceType) -> u8 {
+    match device_type {
+        wgpu::DeviceType::DiscreteGpu => 0 ,
+        wgpu::DeviceType::IntegratedGpu => 1 ,
+        wgpu::DeviceType::VirtualGpu => 2 ,
+        wgpu::DeviceType::Other => 3 ,
+        wgpu::DeviceType::Cpu => 4 ,
+    }
+}
+
+#[repr(C)]
+#[derive(Clone , Debug)]
+/// since v7.0.0 describes gpu devices which create_computing_gpu_resources
+/// can pick , fields are compared with GPUDeviceInfo of the devices and
+/// zero fields and NULL name match every device
+pub struct GPUDeviceSelector {
+    /// when it is true only devices of device_type match
+    pub match_device_type : bool ,
+    pub device_type : GPUDeviceType ,
+    /// vendor ID of the device , 0 matches every vendor
+    pub vendor : u32 ,
+    /// device id of the device , 0 matches every device
+    pub device : u32 ,
+    /// part of the device name , case is ignored
+    pub name : *const c_char ,
+}
+
+impl GPUDeviceSelector {
+    /// name must be NULL or point to a valid C string
+    pub(crate) unsafe fn to_device_selector(&self) -> DeviceSelector {
+        DeviceSelector {
+            device_type : self.match_device_type.then_some(self.device_type) ,
+            vendor : (self.vendor != 0).then_some(self.vendor) ,
+            device : (self.device != 0).then_some(self.device) ,
+            name : cchar_as_string(self.name) ,
+        }
+    }
+}
+
+#[derive(Clone , Debug , Default , PartialEq , Eq)]
+/// Rust side of GPUDeviceSelector which Context::with_device_selectors
+/// uses , None fields match every device
+pub struct DeviceSelector {
+    pub device_type : Option<GPUDeviceType> ,
+    /// vendor ID of the device
+    pub vendor : Option<u32> ,
+    /// device id of the device
+    pub device : Option<u32> ,
+    /// part of the device name , case is ignored
+    pub name : Option<String> ,
+}
+
+impl DeviceSelector {
+    fn matches(&self , info : &wgpu::AdapterInfo) -> bool {
+        self.device_type.is_none_or(|device_type| GPUDeviceType::from(info.device_type) == device_type)
+            && self.vendor.is_none_or(|vendor| info.vendor == vendor)
+            && self.device.is_none_or(|device| info.device == device)
+            && self.name.as_ref().is_none_or(|name| info.name.to_lowercase().contains(&name.to_lowercase()))
+    }
+}
+
+/// picks adapter for the first selector which matches any of them ,
+/// so the next selectors are fallbacks . between the adapters which
+/// match the same selector discrete gpus are preferred
+pub(crate) fn select_adapter(mut adapters : Vec<wgpu::Adapter> , selectors : &[DeviceSelector]) -> Result<wgpu::Adapter , GPUComputingError> {
+    let infos : Vec<wgpu::AdapterInfo> = adapters.iter()
+        .map(|adapter| adapter.get_info())
+        .collect();
+
+    for selector in selectors {
+        // min_by_key keeps the first one of equally ranked adapters
+        let picked = infos.iter()
+            .enumerate()
+            .filter(|(_ , info)| selector.matches(info))
+            .min_by_key(|(_ , info)| device_type_rank(info.device_type))
+            .map(|(index , _)| index);
+
+        if let Some(index) = picked {
+            return Ok(adapters.swap_remove(index));
+        }
+    }
+
+    let names : Vec<&str> = infos.iter()
+        .map(|info| info.name.as_str())
+        .collect();
+    Err(GPUComputingError::new(GPUComputingStatus::AdapterNotFound , format!("ERROR : none of the {} device_selectors matches gpu devices of the backend , found devices are {:?}" , selectors.len() , names)))
+}
+
 #[repr(C)]
 #[derive(Clone , Debug)]
 /// this struct is used for storing information about
@@ -51,6 +146,14 @@ pub struct GPUDeviceInfo {
     pub driver_info: *const c_char,
     /// corresponding GPUComputingBackend
     pub backend: GPUComputingBackend,
+    /// since v7.0.0 the best limits which the device supports , they
+    /// can be used as gpu_speed_custom of custom_speed settings
+    pub limits: GPUSpeedCustom,
+    /// since v7.0.0 GPU_FEATURE bits of the features which
+    /// the device supports
+    pub features: u64,
+    /// since v7.0.0 what the device supports of WebGPU
+    pub downlevel: GPUDownlevelCapabilities,
 }
 
 impl Drop for GPUDeviceInfo {
@@ -77,6 +180,9 @@ pub struct GPUDevices {
 // Rust RAII
 impl Drop for GPUDevices {
     fn drop(&mut self) {
+        if self.infos.is_null() {
+            return;
+        }
         unsafe {
             let _tmp_vec = Vec::from_raw_parts(self.infos , self.len , self.len);
         }
@@ -107,79 +213,114 @@ fn wgpu_backend_to_gpucomputingbackend(backend : wgpu::Backend) -> GPUComputingB
 }
 
 #[no_mangle]
-/// this function returns GPUDevices of passed GPUComputingBackend
-pub extern "C" fn get_computing_gpu_infos(backend : GPUComputingBackend) -> GPUDevices {
+// SAFETY : devices must point to writable GPUDevices , it is only written and never read
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// this function returns GPUDevices of passed GPUComputingBackend .
+/// since v7.0.0 the GPUDevices is written to devices arg and 
+/// GPUComputingStatus is returned
+pub extern "C" fn get_computing_gpu_infos(backend : GPUComputingBackend , devices : *mut GPUDevices) -> GPUComputingStatus {
+    ffi_call(|| {
+        if devices.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL devices arg provided for get_computing_gpu_infos"));
+        }
 
-    let backender = match backend {
-        GPUComputingBackend::vulkan => {
-            wgpu::Backends::VULKAN
-        },
-        GPUComputingBackend::opengl => {
-            wgpu::Backends::GL
-        },
-        GPUComputingBackend::all => {
-            wgpu::Backends::all()
-        },
-        GPUComputingBackend::default_backend => {
-            wgpu::Backends::default()
-        },
-        GPUComputingBackend::metal => {
-            wgpu::Backends::METAL 
-        },
-        GPUComputingBackend::direct_x12 => {
-            wgpu::Backends::DX12
-        },
-        GPUComputingBackend::highest_support => {
-            wgpu::Backends::PRIMARY
-        },
-        GPUComputingBackend::lowest_support => {
-            wgpu::Backends::SECONDARY
-        },
-        GPUComputingBackend::webgpu => {
-            wgpu::Backends::BROWSER_WEBGPU
-        },
-    };
-
-    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor{
-        backends : backender ,
-        ..Default::default()
-    });
-
-    let adapters = instance.enumerate_adapters(backender);
-    let mut devices_keeper : Vec<GPUDeviceInfo> = Vec::new();
-    for adapter in adapters {
-        let info = adapter.get_info();
-        let name = std::ffi::CString::new(info.name).unwrap();
-        let driver = std::ffi::CString::new(info.driver).unwrap();
-        let driver_info = std::ffi::CString::new(info.driver_info).unwrap();
-        devices_keeper.push(GPUDeviceInfo{
-            vendor : info.vendor ,
-            device : info.device ,
-            name : name.into_raw() ,
-            driver : driver.into_raw() ,
-            driver_info : driver_info.into_raw() ,
-            device_type : GPUDeviceType::from(info.device_type) ,
-            backend : wgpu_backend_to_gpucomputingbackend(info.backend) ,
+        let backender = match backend {
+            GPUComputingBackend::vulkan => {
+                wgpu::Backends::VULKAN
+            },
+            GPUComputingBackend::opengl => {
+                wgpu::Backends::GL
+            },
+            GPUComputingBackend::all => {
+                wgpu::Backends::all()
+            },
+            GPUComputingBackend::default_backend => {
+                wgpu::Backends::default()
+            },
+            GPUComputingBackend::metal => {
+                wgpu::Backends::METAL 
+            },
+            GPUComputingBackend::direct_x12 => {
+                wgpu::Backends::DX12
+            },
+            GPUComputingBackend::highest_support => {
+                wgpu::Backends::PRIMARY
+            },
+            GPUComputingBackend::lowest_support => {
+                wgpu::Backends::SECONDARY
+            },
+            GPUComputingBackend::webgpu => {
+                wgpu::Backends::BROWSER_WEBGPU
+            },
+        };
+
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor{
+            backends : backender ,
+            ..Default::default()
         });
 
-    }
+        let adapters = instance.enumerate_adapters(backender);
+        let mut devices_keeper : Vec<GPUDeviceInfo> = Vec::new();
+        for adapter in adapters {
+            let info = adapter.get_info();
+            // adapter strings never contain NUL bytes in practice ,
+            // but they must not be able to panic across C ABI
+            let name = std::ffi::CString::new(info.name.replace('\0' , "")).unwrap_or_default();
+            let driver = std::ffi::CString::new(info.driver.replace('\0' , "")).unwrap_or_default();
+            let driver_info = std::ffi::CString::new(info.driver_info.replace('\0' , "")).unwrap_or_default();
+            devices_keeper.push(GPUDeviceInfo{
+                vendor : info.vendor ,
+                device : info.device ,
+                name : name.into_raw() ,
+                driver : driver.into_raw() ,
+                driver_info : driver_info.into_raw() ,
+                device_type : GPUDeviceType::from(info.device_type) ,
+                backend : wgpu_backend_to_gpucomputingbackend(info.backend) ,
+                limits : GPUSpeedCustom::from(adapter.limits()) ,
+                features : features_to_bits(adapter.features()) ,
+                downlevel : GPUDownlevelCapabilities::from(adapter.get_downlevel_capabilities()) ,
+            });
 
-    let len = devices_keeper.len();
-    let ptr = devices_keeper.as_ptr();
-    std::mem::forget(devices_keeper);
-    GPUDevices {
-        len : len ,
-        infos : ptr as *mut GPUDeviceInfo ,
-    }
+        }
+
+        // len and capacity must be equal for free_gpu_devices_infos
+        let mut devices_keeper = devices_keeper.into_boxed_slice();
+        let len = devices_keeper.len();
+        let ptr = devices_keeper.as_mut_ptr();
+        std::mem::forget(devices_keeper);
+
+        // devices arg may point to uninitialized memory on C side ,
+        // so it must not be dropped
+        unsafe {
+            std::ptr::write(devices , GPUDevices {
+                len ,
+                infos : ptr ,
+            });
+        }
+
+        Ok(())
+    })
 }
 
 #[no_mangle]
+// SAFETY : devices must point to GPUDevices which get_computing_gpu_infos wrote and which is not freed yet
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 /// this function is used for deallocating GPUDevices type from C side
-pub extern "C" fn free_gpu_devices_infos(devices : *mut GPUDevices) {
-    
-    unsafe {
-        let mut devices = &mut *devices;
-        let tmp_vec = Vec::from_raw_parts(devices.infos , devices.len , devices.len);
-    }
-    /**/
+pub extern "C" fn free_gpu_devices_infos(devices : *mut GPUDevices) -> GPUComputingStatus {
+    ffi_call(|| {
+        if devices.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL devices arg provided for free_gpu_devices_infos"));
+        }
+
+        unsafe {
+            let devices = &mut *devices;
+            if !devices.infos.is_null() {
+                let _tmp_vec = Vec::from_raw_parts(devices.infos , devices.len , devices.len);
+            }
+            devices.infos = std::ptr::null_mut();
+            devices.len = 0;
+        }
+
+        Ok(())
+    })
 }
@@ -0,0 +1,188 @@
+use std::ffi::c_void;
+use std::sync::{Arc , Mutex};
+use std::time::Duration;
+
+use crate::{from_ptr , to_ptr , translate_groups , CKernel , ComputeTask , EMJob , GroupOfBinders , HandleMap , NewAllocations};
+use crate::error::{ffi_call , GPUComputingError , GPUComputingStatus};
+use crate::job::Job;
+
+
+/// since v7.0.0 function which compute_async calls from another
+/// thread when the computing task is finished , status is what
+/// emcompute_job_poll would return and user_data is the pointer
+/// which is passed to compute_async
+pub type GPUJobCallback = Option<extern "C" fn(status : GPUComputingStatus , user_data : *mut c_void)>;
+
+// computing task which C side reaches by EMJob handle
+pub(crate) struct GPUJob {
+    pub(crate) job : Job<'static> ,
+    pub(crate) new_allocations : Option<NewAllocations> ,
+}
+
+impl GPUJob {
+    // writes results of NewAllocation binders to their data
+    // fields once the task is finished
+    fn update(&mut self , finished : Result<bool , GPUComputingError>) -> Result<bool , GPUComputingError> {
+        if !matches!(finished , Ok(false)) {
+            if let Some(new_allocations) = self.new_allocations.take() {
+                new_allocations.finish(finished.is_ok());
+            }
+        }
+        finished
+    }
+}
+
+// jobs are stored with the EMContext handle which they run on ,
+// it is kept outside of the lock of the job because waiting for
+// the job holds that lock
+pub(crate) static GPU_JOBS : Mutex<HandleMap<(usize , Arc<Mutex<GPUJob>>)>> = Mutex::new(HandleMap::new());
+
+fn get_job(job : *mut EMJob) -> Result<Arc<Mutex<GPUJob>> , GPUComputingError> {
+    GPU_JOBS.lock().unwrap()
+        .get(from_ptr(job))
+        .map(|(_ , gpu_job)| Arc::clone(gpu_job))
+        .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidHandle , "ERROR : invalid or destroyed EMJob provided , please use the handle which you received from compute_async function"))
+}
+
+// returns JobPending instead of Success when the task is not finished
+fn job_status<F>(body : F) -> GPUComputingStatus
+where F : FnOnce() -> Result<bool , GPUComputingError> {
+    let mut finished = false;
+    let status = ffi_call(|| {
+        finished = body()?;
+        Ok(())
+    });
+
+    if status == GPUComputingStatus::Success && !finished {
+        GPUComputingStatus::JobPending
+    } else {
+        status
+    }
+}
+
+// callback and user_data only move to the thread which
+// calls the callback
+struct JobCallback {
+    callback : extern "C" fn(GPUComputingStatus , *mut c_void) ,
+    user_data : *mut c_void ,
+}
+
+unsafe impl Send for JobCallback {}
+
+impl JobCallback {
+    fn call(&self , status : GPUComputingStatus) {
+        (self.callback)(status , self.user_data);
+    }
+}
+
+// stores gpu_job which runs on EMContext handle context and starts
+// the thread which waits for it when callback is not NULL
+pub(crate) fn add_gpu_job(context : usize , gpu_job : GPUJob , callback : GPUJobCallback , user_data : *mut c_void) -> Result<*mut EMJob , GPUComputingError> {
+    let gpu_job = Arc::new(Mutex::new(gpu_job));
+
+    let handle = GPU_JOBS.lock().unwrap()
+        .insert((context , Arc::clone(&gpu_job)))
+        .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::OutOfMemory , "ERROR : too many EMJob handles are alive"))?;
+
+    if let Some(callback) = callback {
+        let callback = JobCallback {
+            callback ,
+            user_data ,
+        };
+
+        std::thread::spawn(move || {
+            // waiting without the lock keeps the handle usable from
+            // other threads , only the work of this job is waited for
+            let wait = gpu_job.lock().unwrap().job.waiter();
+            wait();
+
+            let status = job_status(|| {
+                let mut gpu_job = gpu_job.lock().unwrap();
+                let finished = gpu_job.job.wait(None);
+                gpu_job.update(finished)
+            });
+            callback.call(status);
+        });
+    }
+
+    Ok(to_ptr(handle))
+}
+
+
+#[no_mangle]
+// SAFETY : kernel must point to CKernel , data_for_gpu to gpu_data_len GroupOfBinders whose DataBinders stay valid until the job is finished and job to writable EMJob pointer
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 works like compute function but returns right after
+/// the task is submitted to the gpu , so host side can do other work
+/// while gpu side computes . handle of the task is written to job and
+/// the results are copied back to host memory of data_for_gpu when
+/// emcompute_job_poll or emcompute_job_wait finds out that the task is
+/// finished , so host memory of DataBinders must stay valid and unchanged
+/// until then . when callback is not NULL , it is called with user_data
+/// from another thread after the results are copied back .
+/// the handle must be destroyed with emcompute_job_destroy
+pub extern "C" fn compute_async(kernel : *mut CKernel , data_for_gpu : *mut GroupOfBinders , gpu_data_len : usize , callback : GPUJobCallback , user_data : *mut c_void , job : *mut *mut EMJob) -> GPUComputingStatus {
+    ffi_call(|| {
+        if job.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL job provided for compute_async"));
+        }
+
+        let ComputeTask { context : gpu_context , context_handle , kernel , workgroups , bindings , new_allocations } = translate_groups(kernel , data_for_gpu , gpu_data_len)?;
+
+        let real_job = gpu_context.compute_async(&kernel , workgroups , bindings)?;
+
+        let handle = add_gpu_job(context_handle , GPUJob {
+            job : real_job ,
+            new_allocations : Some(new_allocations) ,
+        } , callback , user_data)?;
+
+        unsafe {
+            *job = handle;
+        }
+
+        Ok(())
+    })
+}
+
+#[no_mangle]
+/// since v7.0.0 checks the task of job without blocking , Success is
+/// returned when it is finished and its results are copied back ,
+/// JobPending when gpu side is still working on it , or the error
+/// which the task failed with
+pub extern "C" fn emcompute_job_poll(job : *mut EMJob) -> GPUComputingStatus {
+    job_status(|| {
+        let gpu_job = get_job(job)?;
+        let mut gpu_job = gpu_job.lock().unwrap();
+        let finished = gpu_job.job.poll();
+        gpu_job.update(finished)
+    })
+}
+
+#[no_mangle]
+/// since v7.0.0 blocks until the task of job is finished or timeout_ms
+/// milliseconds passed , negative timeout_ms waits without time limit .
+/// it returns the same values as emcompute_job_poll
+pub extern "C" fn emcompute_job_wait(job : *mut EMJob , timeout_ms : i64) -> GPUComputingStatus {
+    job_status(|| {
+        let gpu_job = get_job(job)?;
+        let mut gpu_job = gpu_job.lock().unwrap();
+        let timeout = u64::try_from(timeout_ms).ok().map(Duration::from_millis);
+        let finished = gpu_job.job.wait(timeout);
+        gpu_job.update(finished)
+    })
+}
+
+#[no_mangle]
+/// since v7.0.0 frees the job handle , when the task is not finished
+/// yet its results are dropped and host memory is left untouched .
+/// tasks which have callback are still finished and their callback is
+/// called , so their host memory must stay valid until then
+pub extern "C" fn emcompute_job_destroy(job : *mut EMJob) -> GPUComputingStatus {
+    ffi_call(|| {
+        GPU_JOBS.lock().unwrap()
+            .remove(from_ptr(job))
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidHandle , "ERROR : invalid or already destroyed EMJob provided for emcompute_job_destroy"))?;
+
+        Ok(())
+    })
+}
@@ -0,0 +1,218 @@
+use std::sync::{Arc , Mutex};
+
+use crate::{from_ptr , to_ptr , translate_binders , CKernel , EMMultiDevice , GroupOfBinders , HandleMap};
+use crate::multi_device::{MultiBinding , MultiDevice};
+use crate::error::{ffi_call , GPUComputingError , GPUComputingStatus};
+
+
+// devices which C side reaches by EMMultiDevice handle , contexts
+// are EMContext handles of the added devices . they are kept outside
+// of the lock of multi_device , which computing tasks hold , so
+// destroying an EMContext does not wait for them
+pub(crate) struct GPUMultiDevice {
+    multi_device : Arc<Mutex<MultiDevice>> ,
+    pub(crate) contexts : Vec<usize> ,
+}
+
+// computing tasks of one handle hold only its own lock , so
+// other handles stay usable while the devices work
+pub(crate) static GPU_MULTI_DEVICES : Mutex<HandleMap<GPUMultiDevice>> = Mutex::new(HandleMap::new());
+
+fn invalid_handle() -> GPUComputingError {
+    GPUComputingError::new(GPUComputingStatus::InvalidHandle , "ERROR : invalid or destroyed EMMultiDevice provided , please use the handle which you received from emcompute_multi_device_create function")
+}
+
+// body gets MultiDevice of the handle and EMContext handles of its devices
+fn with_multi_device<T , F>(multi_device : *mut EMMultiDevice , body : F) -> Result<T , GPUComputingError>
+where F : FnOnce(&mut MultiDevice , &[usize]) -> Result<T , GPUComputingError> {
+    let (real_multi_device , contexts) = GPU_MULTI_DEVICES.lock().unwrap()
+        .get(from_ptr(multi_device))
+        .map(|gpu_multi_device| (Arc::clone(&gpu_multi_device.multi_device) , gpu_multi_device.contexts.clone()))
+        .ok_or_else(invalid_handle)?;
+
+    let mut real_multi_device = real_multi_device.lock().unwrap();
+    body(&mut real_multi_device , &contexts)
+}
+
+
+#[no_mangle]
+// SAFETY : multi_device must point to writable EMMultiDevice pointer
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 creates empty multi device executor and writes its handle
+/// to multi_device . it runs one kernel code on several EMContexts at once ,
+/// so machines with more than one gpu can use all of them for one task
+pub extern "C" fn emcompute_multi_device_create(multi_device : *mut *mut EMMultiDevice) -> GPUComputingStatus {
+    ffi_call(|| {
+        if multi_device.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL multi_device provided for emcompute_multi_device_create"));
+        }
+
+        let handle = GPU_MULTI_DEVICES.lock().unwrap()
+            .insert(GPUMultiDevice {
+                multi_device : Arc::new(Mutex::new(MultiDevice::new())) ,
+                contexts : Vec::new() ,
+            })
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::OutOfMemory , "ERROR : too many EMMultiDevice handles are alive"))?;
+
+        unsafe {
+            *multi_device = to_ptr(handle);
+        }
+
+        Ok(())
+    })
+}
+
+#[no_mangle]
+// SAFETY : kernel must point to CKernel
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 adds the device which context field of kernel points to ,
+/// kernel_code field must be registered on that context and it must be the
+/// same kernel code on every device (its bindings and workgroup size are
+/// compared with the first device) , x , y and z fields are ignored .
+/// multi_device keeps its own reference to the kernel code , so the EMKernel
+/// can be destroyed after it is added .
+/// weight is share of the range which the device gets compared to weights
+/// of the other devices , devices are indexed in the order they are added
+pub extern "C" fn emcompute_multi_device_add(multi_device : *mut EMMultiDevice , kernel : *mut CKernel , weight : f64) -> GPUComputingStatus {
+    ffi_call(|| {
+        if kernel.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL kernel provided for emcompute_multi_device_add"));
+        }
+
+        let kernel = unsafe { &*kernel };
+        let (context , real_kernel) = kernel.get_real_config()?;
+
+        with_multi_device(multi_device , |real_multi_device , _| real_multi_device.add(&context , &real_kernel , weight))?;
+
+        GPU_MULTI_DEVICES.lock().unwrap()
+            .get_mut(from_ptr(multi_device))
+            .ok_or_else(invalid_handle)?
+            .contexts.push(from_ptr(kernel.context));
+
+        Ok(())
+    })
+}
+
+#[no_mangle]
+/// since v7.0.0 changes weight of the device which is added at index
+pub extern "C" fn emcompute_multi_device_set_weight(multi_device : *mut EMMultiDevice , index : usize , weight : f64) -> GPUComputingStatus {
+    ffi_call(|| with_multi_device(multi_device , |real_multi_device , _| real_multi_device.set_weight(index , weight)))
+}
+
+#[no_mangle]
+// SAFETY : weights must point to weights_len writable f64 values
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 writes weights of the devices to weights in the order
+/// they are added , weights_len is len of weights and it must not be
+/// smaller than number of the added devices
+pub extern "C" fn emcompute_multi_device_weights(multi_device : *mut EMMultiDevice , weights : *mut f64 , weights_len : usize) -> GPUComputingStatus {
+    ffi_call(|| {
+        let current = with_multi_device(multi_device , |real_multi_device , _| Ok(real_multi_device.weights()))?;
+
+        if current.is_empty() {
+            return Ok(());
+        }
+        if weights.is_null() || weights_len < current.len() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : weights of emcompute_multi_device_weights must have room for {} devices" , current.len())));
+        }
+
+        let weights : &mut [f64] = unsafe {
+            std::slice::from_raw_parts_mut(weights , current.len())
+        };
+        weights.copy_from_slice(&current);
+
+        Ok(())
+    })
+}
+
+#[no_mangle]
+/// since v7.0.0 sets weights of the devices to the throughput which they
+/// had in the last emcompute_multi_device_compute , so faster devices get
+/// bigger parts next time . devices which had no part yet keep their weight
+pub extern "C" fn emcompute_multi_device_balance(multi_device : *mut EMMultiDevice) -> GPUComputingStatus {
+    ffi_call(|| with_multi_device(multi_device , |real_multi_device , _| {
+        real_multi_device.balance();
+        Ok(())
+    }))
+}
+
+#[no_mangle]
+/// since v7.0.0 binds var<uniform> of 4 uint32_t values at @group(group)
+/// @binding(bind) on every device : offset of its part , len of its part ,
+/// size of the whole range in the divided dimension and 0 for padding ,
+/// so kernel codes can find where their part of the range starts
+pub extern "C" fn emcompute_multi_device_set_range_binding(multi_device : *mut EMMultiDevice , group : u32 , bind : u32) -> GPUComputingStatus {
+    ffi_call(|| with_multi_device(multi_device , |real_multi_device , _| {
+        real_multi_device.set_range_binding(group , bind);
+        Ok(())
+    }))
+}
+
+#[no_mangle]
+// SAFETY : data_for_gpu must point to gpu_data_len GroupOfBinders whose DataBinders are valid until it returns
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 divides range of size_x * size_y items between the devices
+/// proportionally to their weights , runs the parts on all of them at the
+/// same time and blocks until every result is copied back . 1D ranges
+/// (size_y is 1) are divided along x and 2D ranges into bands of rows along y .
+/// DataBinders which have split set to true are divided like the range , so
+/// their data_len must be multiple of the divided size , other DataBinders are
+/// given whole to every device and they must be read only . every device runs
+/// the kernel code on its part like it is the whole range and workgroups are
+/// computed like emcompute_set_workgroups_for_size does , computing buffers
+/// (buffer_index) can not be used because they live on one device . when one
+/// device fails , the parts of the other devices are still waited for and
+/// copied back , then the error of the first failed device is returned
+pub extern "C" fn emcompute_multi_device_compute(multi_device : *mut EMMultiDevice , size_x : u32 , size_y : u32 , data_for_gpu : *mut GroupOfBinders , gpu_data_len : usize) -> GPUComputingStatus {
+    ffi_call(|| with_multi_device(multi_device , |real_multi_device , contexts| {
+        let context = *contexts.first()
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : no device is added to EMMultiDevice"))?;
+
+        if data_for_gpu.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : data_for_gpu arg of emcompute_multi_device_compute function is NULL"));
+        }
+
+        let groups : &[GroupOfBinders] = unsafe { std::slice::from_raw_parts(data_for_gpu , gpu_data_len) };
+        let mut split = Vec::new();
+        for group in groups {
+            for binder in group.binders()? {
+                if binder.buffer_index >= 0 {
+                    return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : DataBinder with bind index {} of group {} uses computing buffer , they can not be used by emcompute_multi_device_compute" , binder.bind , group.group)));
+                }
+                split.push(binder.split);
+            }
+        }
+
+        // no computing buffers are left , so DataBinders do not depend on
+        // the context and each device checks them against its own kernel code
+        let (bindings , new_allocations) = translate_binders(to_ptr(context) , groups)?;
+
+        let bindings = bindings.into_iter()
+            .zip(split)
+            .map(|(binding , split)| if split {
+                MultiBinding::Split(binding)
+            } else {
+                MultiBinding::Whole(binding)
+            })
+            .collect();
+
+        let result = real_multi_device.compute([size_x , size_y] , bindings);
+        new_allocations.finish(result.is_ok());
+
+        result
+    }))
+}
+
+#[no_mangle]
+/// since v7.0.0 frees multi_device , the EMContexts and kernel codes which
+/// are added to it are not freed . destroying one of those EMContexts
+/// destroys multi_device too
+pub extern "C" fn emcompute_multi_device_destroy(multi_device : *mut EMMultiDevice) -> GPUComputingStatus {
+    ffi_call(|| {
+        GPU_MULTI_DEVICES.lock().unwrap()
+            .remove(from_ptr(multi_device))
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidHandle , "ERROR : invalid or already destroyed EMMultiDevice provided for emcompute_multi_device_destroy"))?;
+
+        Ok(())
+    })
+}
@@ -0,0 +1,222 @@
+// handles which C side receives are slot index and generation
+// of the slot packed into the pointer value , they are never
+// dereferenced . when a slot is freed its generation changes ,
+// so stale handles are rejected instead of reaching the value
+// which reuses the slot
+
+const INDEX_BITS : u32 = usize::BITS / 2;
+const INDEX_MASK : usize = (1 << INDEX_BITS) - 1;
+const GENERATION_MASK : usize = usize::MAX >> INDEX_BITS;
+
+
+/// opaque handle of gpu resources which create_computing_gpu_resources
+/// allocates , it must be destroyed with emcompute_context_destroy
+pub struct EMContext {
+    _private : [u8; 0] ,
+}
+
+/// opaque handle of registered kernel code , it must be
+/// destroyed with emcompute_kernel_destroy
+pub struct EMKernel {
+    _private : [u8; 0] ,
+}
+
+/// opaque handle of computing task which compute_async submitted ,
+/// it must be destroyed with emcompute_job_destroy
+pub struct EMJob {
+    _private : [u8; 0] ,
+}
+
+/// opaque handle of recorded dispatches which
+/// emcompute_command_list_create allocates , it must be
+/// destroyed with emcompute_command_list_destroy
+pub struct EMCommandList {
+    _private : [u8; 0] ,
+}
+
+/// opaque handle of devices which emcompute_multi_device_create
+/// allocates , it must be destroyed with emcompute_multi_device_destroy
+pub struct EMMultiDevice {
+    _private : [u8; 0] ,
+}
+
+
+pub(crate) fn to_ptr<T>(handle : usize) -> *mut T {
+    std::ptr::without_provenance_mut(handle)
+}
+
+pub(crate) fn from_ptr<T>(ptr : *mut T) -> usize {
+    ptr.addr()
+}
+
+
+struct Slot<T> {
+    generation : usize ,
+    value : Option<T> ,
+}
+
+pub(crate) struct HandleMap<T> {
+    slots : Vec<Slot<T>> ,
+    free_slots : Vec<usize> ,
+}
+
+impl<T> HandleMap<T> {
+    pub(crate) const fn new() -> Self {
+        HandleMap {
+            slots : Vec::new() ,
+            free_slots : Vec::new() ,
+        }
+    }
+
+    // index is stored plus one , so handles are never NULL
+    fn encode(index : usize , generation : usize) -> usize {
+        (generation << INDEX_BITS) | (index + 1)
+    }
+
+    fn decode(handle : usize) -> Option<(usize , usize)> {
+        let index = (handle & INDEX_MASK).checked_sub(1)?;
+        Some((index , handle >> INDEX_BITS))
+    }
+
+    /// returns None when there is no slot left
+    pub(crate) fn insert(&mut self , value : T) -> Option<usize> {
+        match self.free_slots.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                slot.value = Some(value);
+                Some(Self::encode(index , slot.generation))
+            },
+            None => {
+                let index = self.slots.len();
+                if index >= INDEX_MASK {
+                    return None;
+                }
+                self.slots.push(Slot {
+                    generation : 0 ,
+                    value : Some(value) ,
+                });
+                Some(Self::encode(index , 0))
+            },
+        }
+    }
+
+    pub(crate) fn get(&self , handle : usize) -> Option<&T> {
+        let (index , generation) = Self::decode(handle)?;
+        self.slots.get(index)
+            .filter(|slot| slot.generation == generation)
+            .and_then(|slot| slot.value.as_ref())
+    }
+
+    pub(crate) fn get_mut(&mut self , handle : usize) -> Option<&mut T> {
+        let (index , generation) = Self::decode(handle)?;
+        self.slots.get_mut(index)
+            .filter(|slot| slot.generation == generation)
+            .and_then(|slot| slot.value.as_mut())
+    }
+
+    pub(crate) fn remove(&mut self , handle : usize) -> Option<T> {
+        let (index , generation) = Self::decode(handle)?;
+        let slot = self.slots.get_mut(index)
+            .filter(|slot| slot.generation == generation)?;
+        let value = slot.value.take()?;
+
+        slot.generation = (slot.generation + 1) & GENERATION_MASK;
+        // slots which used every generation are retired , so
+        // their handles can never become valid again
+        if slot.generation != 0 {
+            self.free_slots.push(index);
+        }
+
+        Some(value)
+    }
+
+    /// removes every value which matches f
+    pub(crate) fn remove_where<F : FnMut(&T) -> bool>(&mut self , mut f : F) -> Vec<T> {
+        let handles : Vec<usize> = self.slots.iter().enumerate()
+            .filter(|(_ , slot)| slot.value.as_ref().is_some_and(&mut f))
+            .map(|(index , slot)| Self::encode(index , slot.generation))
+            .collect();
+
+        handles.into_iter().filter_map(|handle| self.remove(handle)).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freed_slot_is_reused_with_new_generation() {
+        let mut map = HandleMap::new();
+        let first = map.insert("first").unwrap();
+        let second = map.insert("second").unwrap();
+
+        assert_eq!(map.remove(first) , Some("first"));
+        let third = map.insert("third").unwrap();
+
+        // same slot index , other generation
+        assert_eq!(third & INDEX_MASK , first & INDEX_MASK);
+        assert_ne!(third , first);
+        assert_eq!(map.slots.len() , 2);
+        assert_eq!(map.get(third) , Some(&"third"));
+        assert_eq!(map.get(second) , Some(&"second"));
+    }
+
+    #[test]
+    fn stale_handle_is_rejected() {
+        let mut map = HandleMap::new();
+        let stale = map.insert(1).unwrap();
+        map.remove(stale);
+        let current = map.insert(2).unwrap();
+
+        assert_eq!(map.get(stale) , None);
+        assert_eq!(map.get_mut(stale) , None);
+        assert_eq!(map.remove(stale) , None);
+        assert_eq!(map.get(current) , Some(&2));
+    }
+
+    #[test]
+    fn null_and_unknown_handles_are_rejected() {
+        let mut map = HandleMap::new();
+        let handle = map.insert(1).unwrap();
+
+        assert_ne!(handle , 0);
+        assert_eq!(map.get(0) , None);
+        assert_eq!(map.get(HandleMap::<i32>::encode(5 , 0)) , None);
+    }
+
+    #[test]
+    fn slot_which_used_every_generation_is_retired() {
+        let mut map = HandleMap::new();
+        let handle = map.insert(1).unwrap();
+        map.remove(handle);
+
+        let (index , _) = HandleMap::<i32>::decode(handle).unwrap();
+        map.slots[index].generation = GENERATION_MASK;
+        let last = map.insert(2).unwrap();
+        assert_eq!(last & INDEX_MASK , handle & INDEX_MASK);
+        map.remove(last);
+
+        // generation wrapped to 0 , so handle would be valid again
+        let next = map.insert(3).unwrap();
+        assert_ne!(next & INDEX_MASK , handle & INDEX_MASK);
+        assert_eq!(map.get(handle) , None);
+        assert_eq!(map.get(next) , Some(&3));
+    }
+
+    #[test]
+    fn remove_where_frees_matching_slots() {
+        let mut map = HandleMap::new();
+        let handles : Vec<usize> = (0..4).map(|value| map.insert(value).unwrap()).collect();
+
+        let mut removed = map.remove_where(|value| value % 2 == 0);
+        removed.sort();
+        assert_eq!(removed , vec![0 , 2]);
+
+        assert_eq!(map.get(handles[0]) , None);
+        assert_eq!(map.get(handles[1]) , Some(&1));
+        assert_eq!(map.get(handles[2]) , None);
+        assert_eq!(map.get(handles[3]) , Some(&3));
+    }
+}
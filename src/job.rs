@@ -0,0 +1,200 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool , AtomicUsize , Ordering};
+use std::time::{Duration , Instant};
+
+use crate::binding::{Binding , BindingResource};
+use crate::error::{GPUComputingError , GPUComputingStatus};
+
+
+// submitted gpu work and staging buffers of the bindings
+// which must be read back after it
+pub(crate) struct Submission {
+    device : Arc<wgpu::Device> ,
+    index : wgpu::SubmissionIndex ,
+    staging_buffers : Vec<(usize , wgpu::Buffer)> ,
+    // staging buffers which are not mapped yet , plus
+    // one for the submitted work itself
+    pending : Arc<AtomicUsize> ,
+    map_failed : Arc<AtomicBool> ,
+}
+
+impl Submission {
+    /// must be called right after the work is submitted to queue ,
+    /// index is what queue.submit returned
+    pub(crate) fn new(device : &Arc<wgpu::Device> , queue : &wgpu::Queue , index : wgpu::SubmissionIndex , staging_buffers : Vec<(usize , wgpu::Buffer)>) -> Self {
+        let pending = Arc::new(AtomicUsize::new(staging_buffers.len() + 1));
+        let map_failed = Arc::new(AtomicBool::new(false));
+
+        let work_pending = Arc::clone(&pending);
+        queue.on_submitted_work_done(move || {
+            work_pending.fetch_sub(1 , Ordering::AcqRel);
+        });
+
+        for (_ , staging_buffer) in &staging_buffers {
+            let map_pending = Arc::clone(&pending);
+            let map_failed = Arc::clone(&map_failed);
+            staging_buffer.slice(..).map_async(wgpu::MapMode::Read , move |result| {
+                if result.is_err() {
+                    map_failed.store(true , Ordering::Release);
+                }
+                map_pending.fetch_sub(1 , Ordering::AcqRel);
+            });
+        }
+
+        Submission {
+            device : Arc::clone(device) ,
+            index ,
+            staging_buffers ,
+            pending ,
+            map_failed ,
+        }
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.pending.load(Ordering::Acquire) == 0
+    }
+
+    /// lets wgpu call the callbacks of finished work , when block
+    /// is true it returns after the work of this submission is
+    /// finished , later submissions are not waited for
+    pub(crate) fn poll(&self , block : bool) {
+        let maintain = if block {
+            wgpu::Maintain::wait_for(self.index.clone())
+        } else {
+            wgpu::Maintain::Poll
+        };
+        self.device.poll(maintain);
+    }
+
+    /// returns function which blocks like poll(true) , it does
+    /// not borrow the Submission so it can run on another thread
+    pub(crate) fn waiter(&self) -> impl FnOnce() + Send + 'static {
+        let device = Arc::clone(&self.device);
+        let index = self.index.clone();
+        move || {
+            device.poll(wgpu::Maintain::wait_for(index));
+        }
+    }
+
+    /// copies the mapped staging buffers to host data of bindings ,
+    /// it can only be called when is_done returns true . bindings
+    /// must be in the same order as they were submitted
+    pub(crate) fn read_back<'b , 'c : 'b>(&self , bindings : impl IntoIterator<Item = &'b mut Binding<'c>>) -> Result<() , GPUComputingError> {
+        if self.map_failed.load(Ordering::Acquire) {
+            return Err(GPUComputingError::new(GPUComputingStatus::MapFailed , "ERROR : failed to read back data from gpu"));
+        }
+
+        let mut staging_buffers = self.staging_buffers.iter().peekable();
+
+        for (i , binding) in bindings.into_iter().enumerate() {
+            let Some((_ , staging_buffer)) = staging_buffers.next_if(|(index , _)| *index == i) else {
+                continue;
+            };
+
+            let (data , texture_info) = match &mut binding.resource {
+                BindingResource::Host { data , .. } => (data , None) ,
+                BindingResource::Texture { data , info , .. } => (data , Some(*info)) ,
+                _ => continue ,
+            };
+
+            let out : &mut [u8] = data.as_mut_slice()
+                .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InternalError , "ERROR : read only binding was read back"))?;
+
+            let mapped_data = staging_buffer.slice(..).get_mapped_range();
+            match texture_info {
+                None => out.copy_from_slice(&mapped_data[..out.len()]) ,
+                // rows of the staging buffer are padded
+                Some(info) => {
+                    let row_size = info.row_size() as usize;
+                    for (row , padded_row) in out.chunks_exact_mut(row_size).zip(mapped_data.chunks_exact(info.padded_row_size() as usize)) {
+                        row.copy_from_slice(&padded_row[..row_size]);
+                    }
+                },
+            }
+            drop(mapped_data);
+            staging_buffer.unmap();
+        }
+
+        Ok(())
+    }
+}
+
+
+/// Job is computing task which Context::compute_async or
+/// CommandList::submit submitted ,
+/// host data of its Bindings is borrowed until the Job is dropped
+/// and results are copied to it when poll or wait finds out that
+/// the gpu side work is finished . dropping unfinished Job leaves
+/// host data untouched
+pub struct Job<'a> {
+    bindings : Vec<Binding<'a>> ,
+    submission : Submission ,
+    result : Option<Result<() , GPUComputingError>> ,
+}
+
+impl<'a> Job<'a> {
+    pub(crate) fn new(bindings : Vec<Binding<'a>> , submission : Submission) -> Self {
+        Job {
+            bindings ,
+            submission ,
+            result : None ,
+        }
+    }
+
+    fn finish(&mut self) -> Result<bool , GPUComputingError> {
+        if self.result.is_none() {
+            if !self.submission.is_done() {
+                return Ok(false);
+            }
+            self.result = Some(self.submission.read_back(&mut self.bindings));
+        }
+
+        match &self.result {
+            Some(Err(err)) => Err(err.clone()) ,
+            _ => Ok(true) ,
+        }
+    }
+
+    /// returns true when the Job is finished , it never blocks
+    pub fn poll(&mut self) -> Result<bool , GPUComputingError> {
+        if self.result.is_none() {
+            self.submission.poll(false);
+        }
+        self.finish()
+    }
+
+    /// blocks until the Job is finished or timeout passes , returns
+    /// false when timeout passed first . None timeout waits forever
+    pub fn wait(&mut self , timeout : Option<Duration>) -> Result<bool , GPUComputingError> {
+        let Some(timeout) = timeout else {
+            if self.result.is_none() {
+                self.submission.poll(true);
+            }
+            return self.finish();
+        };
+
+        let start = Instant::now();
+        loop {
+            if self.poll()? {
+                return Ok(true);
+            }
+
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                return Ok(false);
+            }
+            std::thread::sleep((timeout - elapsed).min(Duration::from_micros(200)));
+        }
+    }
+
+    /// returns true when the results are already copied back
+    pub fn is_finished(&self) -> bool {
+        self.result.is_some()
+    }
+
+    // blocks until the gpu side work of the Job is finished
+    // without borrowing it , see Submission::waiter
+    pub(crate) fn waiter(&self) -> impl FnOnce() + Send + 'static {
+        self.submission.waiter()
+    }
+}
@@ -0,0 +1,228 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::context::Context;
+use crate::diagnostic;
+use crate::error::{GPUComputingError , GPUComputingStatus};
+use crate::reflection::{reflect_module , KernelReflection};
+
+
+#[derive(Clone , Debug)]
+/// Kernel is the safe Rust side of registered kernel code ,
+/// it keeps the compiled compute pipeline of the code and
+/// can only be used with the Context which it is created on
+pub struct Kernel {
+    pub(crate) pipeline : Arc<wgpu::ComputePipeline> ,
+    pub(crate) device : Arc<wgpu::Device> ,
+    pub(crate) push_constant_size : u32 ,
+    pub(crate) reflection : Arc<KernelReflection> ,
+}
+
+impl Kernel {
+    /// compiles wgsl code on the context , entry_point is the name
+    /// of the function which your kernel code starts from .
+    /// kernel codes which use var<push_constant> need Context with
+    /// push constants enabled
+    pub fn new(context : &Context , code : &str , entry_point : &str) -> Result<Self , GPUComputingError> {
+        Self::with_constants(context , code , entry_point , &[])
+    }
+
+    /// compiles wgsl code like new and sets values of its override
+    /// declarations , so tile sizes and other constants can be
+    /// specialised per device without changing the code . names of
+    /// constants are names of override declarations , or @id of the
+    /// declarations which have one . @workgroup_size can not use
+    /// overrides yet , Preprocessor can specialise it instead
+    pub fn with_constants(context : &Context , code : &str , entry_point : &str , constants : &[(&str , f64)]) -> Result<Self , GPUComputingError> {
+        let module = naga::front::wgsl::parse_str(code)
+            .map_err(|err| diagnostic::wgsl_error(&err , code))?;
+
+        let constants : HashMap<String , f64> = constants.iter()
+            .map(|(name , value)| (name.to_string() , *value))
+            .collect();
+        for name in constants.keys() {
+            // declarations with @id can only be set by their id
+            let declaration = module.overrides.iter()
+                .find(|(_ , declaration)| declaration.name.as_deref() == Some(name.as_str()) || declaration.id.is_some_and(|id| id.to_string() == *name))
+                .map(|(_ , declaration)| declaration)
+                .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : kernel code has no override declaration named {}" , name)))?;
+            if let Some(id) = declaration.id.filter(|id| id.to_string() != *name) {
+                return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : override {} is declared with @id({}) , so its value must be set with name {}" , name , id , id)));
+            }
+        }
+
+        Self::create(context , wgpu::ShaderSource::Wgsl(code.into()) , &module , Some(code) , entry_point , &constants)
+    }
+
+    /// compiles SPIR-V binary module on the context , entry_point is the
+    /// name of its OpEntryPoint with GLCompute execution model . the module
+    /// is translated by naga , so it works on every backend
+    pub fn from_spirv(context : &Context , spirv : &[u8] , entry_point : &str) -> Result<Self , GPUComputingError> {
+        let module = naga::front::spv::parse_u8_slice(spirv , &naga::front::spv::Options::default())
+            .map_err(|err| diagnostic::spirv_error(&err))?;
+        Self::from_module(context , module , None , entry_point)
+    }
+
+    /// compiles GLSL compute shader on the context , defines are
+    /// set like #define name value before the code and its entry
+    /// point is main function , like every GLSL shader
+    pub fn from_glsl(context : &Context , code : &str , defines : &[(&str , &str)]) -> Result<Self , GPUComputingError> {
+        let mut options = naga::front::glsl::Options::from(naga::ShaderStage::Compute);
+        options.defines.extend(defines.iter().map(|(name , value)| (name.to_string() , value.to_string())));
+
+        let module = naga::front::glsl::Frontend::default().parse(&options , code)
+            .map_err(|err| diagnostic::glsl_error(&err , code))?;
+        Self::from_module(context , module , Some(code) , "main")
+    }
+
+    // code is the source of module when it has text
+    fn from_module(context : &Context , module : naga::Module , code : Option<&str> , entry_point : &str) -> Result<Self , GPUComputingError> {
+        let source = wgpu::ShaderSource::Naga(Cow::Owned(module.clone()));
+        Self::create(context , source , &module , code , entry_point , &HashMap::new())
+    }
+
+    fn create(context : &Context , source : wgpu::ShaderSource , module : &naga::Module , code : Option<&str> , entry_point : &str , constants : &HashMap<String , f64>) -> Result<Self , GPUComputingError> {
+        let device = &context.device;
+        let compilation_options = wgpu::PipelineCompilationOptions {
+            constants ,
+            ..Default::default()
+        };
+
+        // naga validates the code before wgpu , so the problems
+        // are returned with their location in the code
+        let info = naga::valid::Validator::new(naga::valid::ValidationFlags::all() , naga::valid::Capabilities::all())
+            .validate(module)
+            .map_err(|err| diagnostic::validation_error(&err , code))?;
+
+        let reflection = reflect_module(module , &info , entry_point)?;
+        let push_constants = Some(&reflection).filter(|reflection| reflection.push_constant_size > 0);
+        if push_constants.is_some() && !device.features().contains(wgpu::Features::PUSH_CONSTANTS) {
+            return Err(GPUComputingError::new(GPUComputingStatus::UnsupportedFeature , "ERROR : kernel code uses var<push_constant> but push constants are not enabled on the gpu device , set push_constants field of GPUComputingConfig to true on a device which supports them"));
+        }
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Shader"),
+            source,
+        });
+
+        let mut compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: None,
+            module: &shader,
+            entry_point ,
+            compilation_options: compilation_options.clone(),
+            cache: None,
+        });
+
+        // derived layouts do not contain push constant ranges , so
+        // the pipeline is created again with its bind group layouts
+        // and the push constant range
+        if let Some(push_constants) = push_constants {
+            let bind_group_layouts : Vec<wgpu::BindGroupLayout> = (0..push_constants.bind_group_count())
+                .map(|group| compute_pipeline.get_bind_group_layout(group))
+                .collect();
+            let bind_group_layouts : Vec<&wgpu::BindGroupLayout> = bind_group_layouts.iter().collect();
+
+            let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &bind_group_layouts,
+                push_constant_ranges: &[wgpu::PushConstantRange {
+                    stages: wgpu::ShaderStages::COMPUTE,
+                    range: 0..push_constants.push_constant_size,
+                }],
+            });
+
+            compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: None,
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point ,
+                compilation_options,
+                cache: None,
+            });
+        }
+
+        if let Some(err) = pollster::block_on(device.pop_error_scope()) {
+            return Err(GPUComputingError::new(GPUComputingStatus::ShaderCompileError , format!("ERROR : could not compile the kernel code : {}" , err)));
+        }
+
+        Ok(Kernel {
+            pipeline : Arc::new(compute_pipeline) ,
+            device : Arc::clone(device) ,
+            push_constant_size : push_constants.map_or(0 , |push_constants| push_constants.push_constant_size) ,
+            reflection : Arc::new(reflection) ,
+        })
+    }
+
+    /// returns bindings , workgroup size and push constant
+    /// size which the entry point of the kernel code uses
+    pub fn reflection(&self) -> &KernelReflection {
+        &self.reflection
+    }
+
+    /// @workgroup_size of the entry point
+    pub fn workgroup_size(&self) -> [u32; 3] {
+        self.reflection.workgroup_size
+    }
+
+    /// returns number of workgroups in x , y and z dimensions which
+    /// cover size elements with @workgroup_size of the entry point ,
+    /// unused dimensions of size must be 1 .
+    /// when a count is bigger than max_compute_workgroups_per_dimension
+    /// of the device , it is split and the rest is moved to the next
+    /// dimension , so kernel codes which can receive such sizes must
+    /// compute their element index from @builtin(num_workgroups) and
+    /// every kernel code must skip the indices which are out of size
+    pub fn workgroups_for_size(&self , size : [u32; 3]) -> Result<[u32; 3] , GPUComputingError> {
+        workgroups_for(size , self.workgroup_size() , self.device.limits().max_compute_workgroups_per_dimension)
+    }
+}
+
+fn workgroups_for(size : [u32; 3] , workgroup_size : [u32; 3] , max : u32) -> Result<[u32; 3] , GPUComputingError> {
+    let mut counts : [u32; 3] = std::array::from_fn(|i| size[i].div_ceil(workgroup_size[i]));
+
+    for i in 0..2 {
+        if counts[i] > max {
+            let parts = counts[i].div_ceil(max);
+            counts[i] = counts[i].div_ceil(parts);
+            counts[i + 1] = counts[i + 1].saturating_mul(parts);
+        }
+    }
+
+    if counts[2] > max {
+        return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : size {:?} needs more workgroups than the device supports , max_compute_workgroups_per_dimension is {}" , size , max)));
+    }
+
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_within_max_are_not_split() {
+        assert_eq!(workgroups_for([100 , 1 , 1] , [64 , 1 , 1] , 65535).unwrap() , [2 , 1 , 1]);
+        assert_eq!(workgroups_for([64 * 65535 , 3 , 5] , [64 , 2 , 1] , 65535).unwrap() , [65535 , 2 , 5]);
+    }
+
+    #[test]
+    fn counts_above_max_move_to_next_dimension() {
+        assert_eq!(workgroups_for([64 * 65535 + 1 , 1 , 1] , [64 , 1 , 1] , 65535).unwrap() , [32768 , 2 , 1]);
+        assert_eq!(workgroups_for([u32::MAX , 1 , 1] , [64 , 1 , 1] , 65535).unwrap() , [65473 , 1025 , 1]);
+        assert_eq!(workgroups_for([1 , 65536 , 3] , [1 , 1 , 1] , 65535).unwrap() , [1 , 32768 , 6]);
+    }
+
+    #[test]
+    fn counts_above_max_in_z_are_rejected() {
+        let err = workgroups_for([1 , u32::MAX , 1] , [1 , 1 , 1] , 65535).unwrap_err();
+        assert_eq!(err.status , GPUComputingStatus::InvalidArgument);
+
+        let err = workgroups_for([1 , 1 , 65536] , [1 , 1 , 1] , 65535).unwrap_err();
+        assert_eq!(err.status , GPUComputingStatus::InvalidArgument);
+        assert!(err.message.contains("max_compute_workgroups_per_dimension is 65535") , "{}" , err.message);
+    }
+}
@@ -1,7 +1,7 @@
 /*! fast , simple and cross-platform GPGPU parallel computing library
 NOTE : there are still some problems with vulkan backend on linux 
 ##Example
-- this example is for v6.0.0 C ABI 
+- this example is for v7.0.0 C ABI 
  ```c
 #include <stdio.h>
 #include <stdint.h>
@@ -9,15 +9,14 @@ NOTE : there are still some problems with vulkan backend on linux
 #include "EMCompute.h"
 
 int main() {
-  GPUDevices infos = get_computing_gpu_infos(0);
-  printf("%s\n" , infos.infos[0].name);
+  GPUDevices infos = {0};
+  if (get_computing_gpu_infos(0 , &infos) == Success && infos.len > 0) {
+    printf("%s\n" , infos.infos[0].name);
+  }
   free_gpu_devices_infos(&infos);
 
   // Define the kernel
   CKernel kernel;
-  kernel.x = 60000;  // Number of workgroups in the x dimension
-  kernel.y = 1000;
-  kernel.z = 100;
 
   // WGSL code to perform element-wise addition of example_data and example_data0
   const char* code = 
@@ -25,14 +24,27 @@ int main() {
     "@group(0)@binding(1) var<storage, read_write> v_indices0: array<u32>; "
     "@compute @workgroup_size(10 , 1 , 1)" 
     "fn main(@builtin(global_invocation_id) global_id: vec3<u32>) { "
-    "  let idx = global_id.x % 60000; "
-    "   "
+    "  let idx = global_id.x; "
+    "  if (idx >= arrayLength(&v_indices)) { return; } "
     "v_indices[idx] = v_indices[idx] + v_indices0[idx]; "
     "  "
     "}";
 
-  uintptr_t index = set_kernel_default_config(&kernel);
-  kernel.kernel_code_index = register_computing_kernel_code(index , code , "main");
+  // since v7.0.0 every function returns GPUComputingStatus
+  if (set_kernel_default_config(&kernel) != Success) {
+    printf("%s\n" , emcompute_last_error());
+    return 1;
+  }
+  if (register_computing_kernel_code(kernel.context , code , "main" , &kernel.kernel_code) != Success) {
+    printf("%s\n" , emcompute_last_error());
+    return 1;
+  }
+  // since v7.0.0 x , y and z fields of kernel can be computed from
+  // number of elements and @workgroup_size of the kernel code
+  if (emcompute_set_workgroups_for_size(&kernel , 60000 , 1 , 1) != Success) {
+    printf("%s\n" , emcompute_last_error());
+    return 1;
+  }
 
 
 
@@ -46,15 +58,23 @@ int main() {
   }
 
   // Bind data
-  DataBinder data;
+  DataBinder data = {0}; // fields which are not set below (texture , sampler , split) stay zero
   data.bind = 0;
   data.data = (uint8_t **)&example_data;
   data.data_len = sizeof(uint32_t)*60000/sizeof(uint8_t);
+  data.buffer_index = -1; // data field is used instead of computing buffer
+  data.access = ReadWrite;
+  data.read_back_mode = InPlace; // results are copied to example_data
+  data.kind = Storage; // var<storage> in kernel code
 
-  DataBinder data0;
+  DataBinder data0 = {0};
   data0.bind = 1;
   data0.data = (uint8_t **)&example_data0;
   data0.data_len = sizeof(uint32_t)*60000/sizeof(uint8_t);
+  data0.buffer_index = -1;
+  data0.access = ReadOnly; // example_data0 is only input , so it is not read back
+  data0.read_back_mode = InPlace;
+  data0.kind = Storage;
 
   DataBinder group0[] = {data, data0};
   GroupOfBinders wrapper;
@@ -64,7 +84,9 @@ int main() {
 
   GroupOfBinders groups[] = {wrapper};
   //for (int i = 0 ; i < 10000 ;++i){
-    compute(&kernel, groups, 1);
+    if (compute(&kernel, groups, 1) != Success) {
+      printf("%s\n" , emcompute_last_error());
+    }
   //}
 
   
@@ -75,19 +97,46 @@ int main() {
 
   free(example_data0);
   free(example_data);
-  free_compute_cache();
+  emcompute_kernel_destroy(kernel.kernel_code);
+  emcompute_context_destroy(kernel.context);
 
   return 0;
 }
 ```
+- since v7.0.0 the same api can be used from Rust 
+ ```no_run
+use EMCompute::{Context , Kernel , Buffer , Binding};
+
+let context = Context::from_default_config()?;
+
+let kernel = Kernel::new(&context , 
+    "@group(0)@binding(0) var<storage, read_write> v_indices: array<u32>; \
+     @group(0)@binding(1) var<storage, read> v_indices0: array<u32>; \
+     @compute @workgroup_size(10 , 1 , 1) \
+     fn main(@builtin(global_invocation_id) global_id: vec3<u32>) { \
+       let idx = global_id.x; \
+       if (idx >= arrayLength(&v_indices)) { return; } \
+       v_indices[idx] = v_indices[idx] + v_indices0[idx]; \
+     }" , "main")?;
+
+let mut example_data = vec![1u32; 60000];
+let example_data0 = Buffer::from_slice(&context , &vec![1u32; 60000])?;
+
+let workgroups = kernel.workgroups_for_size([60000 , 1 , 1])?;
+context.compute(&kernel , workgroups , &mut [
+    Binding::read_write(0 , 0 , &mut example_data) ,
+    Binding::buffer(0 , 1 , &example_data0) ,
+])?;
+
+assert_eq!(example_data[4] , 2);
+# Ok::<() , EMCompute::GPUComputingError>(())
+```
 */
 
 
 use std::os::raw::c_char;
 use std::ffi::CStr;
 
-use wgpu::util::DeviceExt;
-
 use std::sync::{Arc, Mutex};
 
 use core::ops::Range;
@@ -103,243 +152,550 @@ pub use configuration::
 mod util;
 use util::{cchar_as_string};
 
+mod error;
+pub use error::
+{GPUComputingStatus , 
+    GPUComputingError , 
+    emcompute_last_error};
+use error::ffi_call;
+
+mod diagnostic;
+pub use diagnostic::{
+    CompileDiagnostic ,
+    GPUCompileDiagnostic ,
+    emcompute_last_compile_diagnostics ,
+};
+
+mod features;
+pub use features::*;
+
+mod handle;
+pub use handle::
+{EMContext , 
+    EMKernel , 
+    EMJob , 
+    EMCommandList , 
+    EMMultiDevice};
+use handle::{from_ptr , to_ptr , HandleMap};
+
+mod context;
+pub use context::Context;
+
+mod kernel;
+pub use kernel::Kernel;
+
+mod buffer;
+pub use buffer::Buffer;
+
+mod binding;
+pub use binding::Binding;
+
+mod preprocessor;
+pub use preprocessor::{Preprocessor , Preprocessed , SourceLine};
+
+mod job;
+pub use job::Job;
+
+mod command_list;
+pub use command_list::CommandList;
+
+mod multi_device;
+pub use multi_device::{MultiDevice , MultiBinding};
+
+mod texture;
+pub use texture::
+{GPUTextureFormat , 
+    GPUTextureDimension , 
+    GPUTextureInfo , 
+    GPUFilterMode , 
+    GPUAddressMode , 
+    GPUSamplerInfo};
+
+mod reflection;
+pub use reflection::
+{GPUScalarKind , 
+    GPUBindingInfo , 
+    KernelReflection , 
+    GPUKernelReflection , 
+    emcompute_kernel_reflect , 
+    emcompute_free_kernel_reflection};
+
+mod gpu_buffer;
+pub use gpu_buffer::
+{create_computing_buffer , 
+    write_computing_buffer , 
+    read_computing_buffer , 
+    resize_computing_buffer , 
+    free_computing_buffer};
+
+mod gpu_job;
+pub use gpu_job::
+{GPUJobCallback , 
+    compute_async , 
+    emcompute_job_poll , 
+    emcompute_job_wait , 
+    emcompute_job_destroy};
+
+mod gpu_command_list;
+pub use gpu_command_list::
+{emcompute_command_list_create , 
+    emcompute_command_list_dispatch , 
+    emcompute_command_list_dispatch_with_push_constants , 
+    emcompute_command_list_dispatch_indirect , 
+    emcompute_command_list_run , 
+    emcompute_command_list_run_async , 
+    emcompute_command_list_destroy};
+
+mod gpu_multi_device;
+pub use gpu_multi_device::
+{emcompute_multi_device_create , 
+    emcompute_multi_device_add , 
+    emcompute_multi_device_set_weight , 
+    emcompute_multi_device_weights , 
+    emcompute_multi_device_balance , 
+    emcompute_multi_device_set_range_binding , 
+    emcompute_multi_device_compute , 
+    emcompute_multi_device_destroy};
+
 mod gpu_device;
 pub use gpu_device::
 {GPUDeviceType , 
     GPUDeviceInfo , 
     GPUDevices , 
+    GPUDeviceSelector , 
+    DeviceSelector , 
     get_computing_gpu_infos , 
     free_gpu_devices_infos};
 
 
 
-struct GPUDeviceCollection {
-    compute_pipeline : Arc<wgpu::ComputePipeline> ,
+// resources which C side reaches by EMContext handle
+// and buffer_index
+struct GPUCollection {
+    context : Context ,
+    buffers : Vec<Option<Arc<Mutex<Buffer<u8>>>>> ,
+    // source snippets which kernel codes can #include
+    preprocessor : Preprocessor ,
 }
 
-struct GPUCollection {
-    device : Arc<wgpu::Device> ,
-    queue : Arc<wgpu::Queue> ,
-    res : Option<Arc<Mutex<Vec<GPUDeviceCollection>>>> ,
+// kernel code which C side reaches by EMKernel handle ,
+// context is the handle which it is registered on
+struct GPUKernel {
+    kernel : Kernel ,
+    context : usize ,
 }
 
 
-static mut GPU_RES_KEEPER : Option<Arc<Mutex<Vec<GPUCollection>>>> = None;
+static GPU_CONTEXTS : Mutex<HandleMap<GPUCollection>> = Mutex::new(HandleMap::new());
+static GPU_KERNELS : Mutex<HandleMap<GPUKernel>> = Mutex::new(HandleMap::new());
+
+
+// runs body on GPUCollection of context while 
+// GPU_CONTEXTS is locked
+fn with_gpu_collection<T , F>(context : *mut EMContext , body : F) -> Result<T , GPUComputingError>
+where F : FnOnce(&mut GPUCollection) -> Result<T , GPUComputingError> {
+    let mut contexts = GPU_CONTEXTS.lock().unwrap();
+    match contexts.get_mut(from_ptr(context)) {
+        None => Err(GPUComputingError::new(GPUComputingStatus::InvalidHandle , "ERROR : invalid or destroyed EMContext provided , please use the handle which you received from create_computing_gpu_resources function")),
+        Some(collection) => body(collection),
+    }
+}
+
+fn add_gpu_collection(context : Context) -> Result<*mut EMContext , GPUComputingError> {
+    let handle = GPU_CONTEXTS.lock().unwrap()
+        .insert(GPUCollection{
+            context ,
+            buffers : Vec::new() ,
+            preprocessor : Preprocessor::new() ,
+        })
+        .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::OutOfMemory , "ERROR : too many EMContext handles are alive"))?;
+
+    Ok(to_ptr(handle))
+}
 
 
 #[no_mangle]
+// SAFETY : device_selectors of config must point to device_selectors_len GPUDeviceSelectors whose name is NULL or NUL terminated , context must point to writable EMContext pointer
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 /// since v4.0.0 you must create_computing_gpu_resources 
-/// it will return gpu_res_descriptor as uintptr_t (usize) 
-/// and you have to pass it as config_index value to 
-/// CKernel variable
-pub extern "C" fn create_computing_gpu_resources(config : GPUComputingConfig , customize : GPUCustomSettings) -> usize {
-
-    let backend = match config.backend {
-            GPUComputingBackend::vulkan => {
-                wgpu::Backends::VULKAN
-            },
-            GPUComputingBackend::opengl => {
-                wgpu::Backends::GL
-            },
-            GPUComputingBackend::all => {
-                wgpu::Backends::all()
-            },
-            GPUComputingBackend::default_backend => {
-                wgpu::Backends::default()
-            },
-            GPUComputingBackend::metal => {
-                wgpu::Backends::METAL 
-            },
-            GPUComputingBackend::direct_x12 => {
-                wgpu::Backends::DX12
-            },
-            GPUComputingBackend::highest_support => {
-                wgpu::Backends::PRIMARY
-            },
-            GPUComputingBackend::lowest_support => {
-                wgpu::Backends::SECONDARY
-            },
-            GPUComputingBackend::webgpu => {
-                wgpu::Backends::BROWSER_WEBGPU
-            },
+/// it will write EMContext handle to context and you 
+/// have to pass it as context value to CKernel variable .
+/// since v7.0.0 it returns GPUComputingStatus instead of panicking
+/// and the handle must be destroyed by emcompute_context_destroy
+pub extern "C" fn create_computing_gpu_resources(config : GPUComputingConfig , customize : GPUCustomSettings , context : *mut *mut EMContext) -> GPUComputingStatus {
+    ffi_call(|| {
+        if context.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL context provided for create_computing_gpu_resources"));
+        }
+
+        if config.device_selectors.is_null() && config.device_selectors_len != 0 {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : device_selectors of GPUComputingConfig is NULL but device_selectors_len is not 0"));
+        }
+
+        let selectors : Vec<DeviceSelector> = if config.device_selectors_len == 0 {
+            Vec::new()
+        } else {
+            let selectors = unsafe {
+                std::slice::from_raw_parts(config.device_selectors , config.device_selectors_len)
+            };
+            selectors.iter()
+                .map(|selector| unsafe { selector.to_device_selector() })
+                .collect()
         };
 
-    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor{
-        backends : backend ,
-        ..Default::default()
-    });
-
-
-    let mut adapter : wgpu::Adapter;
-
-    if config.gpu_index_in_backend_group < 0 {
-        let adapt = pollster::block_on(instance
-        .request_adapter(&wgpu::RequestAdapterOptions{
-            power_preference : match config.power {
-                GPUPowerSettings::none => {
-                    wgpu::PowerPreference::None
-                },
-                GPUPowerSettings::LowPower => {
-                    wgpu::PowerPreference::LowPower
-                },
-                GPUPowerSettings::HighPerformance => {
-                    wgpu::PowerPreference::HighPerformance
-                },
-            },
-            ..Default::default()
-        }))
-    .expect("ERROR : could not allocate gpu resources which match your configs");
-        adapter = adapt;
-
-    }else {
-        let mut adapters = instance.enumerate_adapters(backend);
-        adapter = adapters.swap_remove(config.gpu_index_in_backend_group as usize);
-    }
+        let handle = add_gpu_collection(Context::with_device_selectors(&config , &customize , &selectors)?)?;
+
+        unsafe {
+            *context = handle;
+        }
+
+        Ok(())
+    })
+}
 
+#[no_mangle]
+/// since v7.0.0 frees gpu resources of context , its computing
+/// buffers , kernel codes which are registered on it , its jobs ,
+/// command lists and multi devices which it is added to .
+/// the handle and its kernel handles can not be used anymore
+pub extern "C" fn emcompute_context_destroy(context : *mut EMContext) -> GPUComputingStatus {
+    ffi_call(|| {
+        let handle = from_ptr(context);
+
+        GPU_CONTEXTS.lock().unwrap()
+            .remove(handle)
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidHandle , "ERROR : invalid or already destroyed EMContext provided for emcompute_context_destroy"))?;
+
+        GPU_KERNELS.lock().unwrap()
+            .remove_where(|kernel| kernel.context == handle);
+
+        gpu_job::GPU_JOBS.lock().unwrap()
+            .remove_where(|(context , _)| *context == handle);
+
+        gpu_command_list::GPU_COMMAND_LISTS.lock().unwrap()
+            .remove_where(|list| list.context == handle);
+
+        gpu_multi_device::GPU_MULTI_DEVICES.lock().unwrap()
+            .remove_where(|multi_device| multi_device.contexts.contains(&handle));
+
+        Ok(())
+    })
+}
 
-    let (device, queue) = pollster::block_on(adapter
-        .request_device(
-            &wgpu::DeviceDescriptor {
-                label: None,
-                required_features: wgpu::Features::empty(),
-                required_limits: match config.speed {
-                    GPUSpeedSettings::lowest_speed => {
-                        wgpu::Limits::downlevel_webgl2_defaults()
-                    },
-                    GPUSpeedSettings::low_speed => {
-                        wgpu::Limits::downlevel_defaults()
-                    },
-                    GPUSpeedSettings::custom_speed => {
-
-                        customize.gpu_speed_custom.to_gpu_limits()
-                    },
-                    GPUSpeedSettings::default_speed => {
-                        wgpu::Limits::default()
-                    },
-                },
-                memory_hints: match config.memory {
-                    GPUMemorySettings::prefer_performance => {
-                        wgpu::MemoryHints::Performance
-                    },
-                    GPUMemorySettings::prefer_memory => {
-                        wgpu::MemoryHints::MemoryUsage
-                    },
-                    GPUMemorySettings::custom_memory => {
-
-
-                        wgpu::MemoryHints::Manual{
-                            suballocated_device_memory_block_size : customize.gpu_memory_custom.to_rs_range(),
-                        }
-                    },
-                },
-            },
-            None,
-            ))
-                .expect("ERROR : could not allocate gpu resources which match your configs");
-
-    // println!("get real done");
-    unsafe{
-        let device = Arc::new(device);
-        let queue = Arc::new(queue);
-
-        if let None = &GPU_RES_KEEPER {
-            GPU_RES_KEEPER = Some(Arc::new(Mutex::new(Vec::new())));
+#[no_mangle]
+// SAFETY : features must point to writable u64
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 writes GPU_FEATURE bits of the features which are
+/// enabled on the gpu device of context to features , they are the
+/// required_features of its GPUComputingConfig and the optional_features
+/// which the device supports
+pub extern "C" fn emcompute_context_features(context : *mut EMContext , features : *mut u64) -> GPUComputingStatus {
+    ffi_call(|| {
+        if features.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL features provided for emcompute_context_features"));
         }
 
-        let arci = GPU_RES_KEEPER.clone().unwrap();
-        let mut GPU_Data = arci.lock().unwrap();
+        let enabled = with_gpu_collection(context , |collection| Ok(collection.context.features()))?;
 
-        let setting_cache_index = GPU_Data.len();
-        GPU_Data.push(GPUCollection{
-            device : Arc::clone(&device) ,
-            queue : Arc::clone(&queue) ,
-            res : None ,
-        });
+        unsafe {
+            *features = enabled;
+        }
 
-        return setting_cache_index;
-    }
+        Ok(())
+    })
+}
+
+#[no_mangle]
+// SAFETY : limits must point to writable GPUSpeedCustom , it is only written and never read
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 writes the limits which gpu device of context is
+/// created with to limits , for clamped_custom_speed they show
+/// which values were actually used
+pub extern "C" fn emcompute_context_limits(context : *mut EMContext , limits : *mut GPUSpeedCustom) -> GPUComputingStatus {
+    ffi_call(|| {
+        if limits.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL limits provided for emcompute_context_limits"));
+        }
+
+        let effective = with_gpu_collection(context , |collection| Ok(collection.context.limits()))?;
+
+        // limits may point to uninitialized memory on C side
+        unsafe {
+            std::ptr::write(limits , effective);
+        }
+
+        Ok(())
+    })
 }
 
 #[no_mangle]
 /// since v4.0.0 your kernel code must be registered before 
-/// you want to use it . gpu_res_index is gpu resource descriptor 
+/// you want to use it . context is gpu resource handle 
 /// which you get from create_computing_gpu_resources .
-pub extern "C" fn register_computing_kernel_code(gpu_res_index : usize , code : *const c_char , entry_point : *const c_char) -> usize {
+/// since v7.0.0 handle of the registered kernel code is written to 
+/// kernel_code and GPUComputingStatus is returned . when the code can
+/// not be compiled ShaderCompileError is returned and
+/// emcompute_last_compile_diagnostics shows where the problems are
+pub extern "C" fn register_computing_kernel_code(context : *mut EMContext , code : *const c_char , entry_point : *const c_char , kernel_code : *mut *mut EMKernel) -> GPUComputingStatus {
+    ffi_call(|| {
+        if kernel_code.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL kernel_code provided for register_computing_kernel_code"));
+        }
+
+        let code = cchar_as_string(code)
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : No computing kernel code provided , code field is not set ."))?;
+        let entry_point = cchar_as_string(entry_point)
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : No code_entry_point field is set , it must be name of function which your kernel code starts from"))?;
+
+        add_kernel(context , kernel_code , |real_context| Kernel::new(real_context , &code , &entry_point))
+    })
+}
+
+// compiles kernel on context and writes its handle to kernel_code
+fn add_kernel<F>(context : *mut EMContext , kernel_code : *mut *mut EMKernel , compile : F) -> Result<() , GPUComputingError>
+where F : FnOnce(&Context) -> Result<Kernel , GPUComputingError> {
+    let real_context = with_gpu_collection(context , |collection| Ok(collection.context.clone()))?;
+
+    let kernel = compile(&real_context)?;
+
+    let handle = GPU_KERNELS.lock().unwrap()
+        .insert(GPUKernel{
+            kernel ,
+            context : from_ptr(context) ,
+        })
+        .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::OutOfMemory , "ERROR : too many EMKernel handles are alive"))?;
+
     unsafe {
-        match &GPU_RES_KEEPER {
-            None => {
-                panic!("ERROR : use create_gpu_resources function first to add and get index of your config !");
-            },
-            Some(arci) => {
-                let mut gpu_data = arci.lock().unwrap();
-                if gpu_data.len() <= gpu_res_index {
-                    panic!("ERROR : invalid gpu_res_index provided for register_kernel_code function , please use the number which you received from create_gpu_resources function");
-                }
+        *kernel_code = to_ptr(handle);
+    }
 
-                let shader = gpu_data[gpu_res_index].device.create_shader_module(wgpu::ShaderModuleDescriptor {
-                    label: Some("Shader"),
-                    source: wgpu::ShaderSource::Wgsl(cchar_as_string(code).expect("ERROR : No computing kernel code provided , code field is not set .").into()),
-                });
+    Ok(())
+}
 
+#[repr(C)]
+/// since v7.0.0 value of one override declaration of wgsl
+/// kernel code , name is its name or its @id as text for
+/// declarations which have @id
+pub struct GPUOverrideConstant {
+    pub name : *const c_char ,
+    pub value : f64 ,
+}
 
+#[no_mangle]
+// SAFETY : code and entry_point must be NULL or NUL terminated , constants must point to constants_len GPUOverrideConstants with NUL terminated names and kernel_code to writable EMKernel pointer
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 works like register_computing_kernel_code and sets
+/// values of override declarations of the code when its pipeline is
+/// created , so tile sizes and other constants can be specialised per
+/// device without changing the code . constants is array of
+/// constants_len GPUOverrideConstant , it can be NULL when constants_len
+/// is 0 . values are converted to the type of their declaration and
+/// declarations which have no default value must be set .
+/// @workgroup_size can not use overrides yet , use
+/// emcompute_register_kernel_preprocessed for specialising it
+pub extern "C" fn emcompute_register_kernel_with_constants(context : *mut EMContext , code : *const c_char , entry_point : *const c_char , constants : *const GPUOverrideConstant , constants_len : usize , kernel_code : *mut *mut EMKernel) -> GPUComputingStatus {
+    ffi_call(|| {
+        if kernel_code.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL kernel_code provided for emcompute_register_kernel_with_constants"));
+        }
+        if constants.is_null() && constants_len != 0 {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL constants provided for emcompute_register_kernel_with_constants with non zero constants_len"));
+        }
 
-                let compute_pipeline = gpu_data[gpu_res_index].device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
-                    label: None,
-                    layout: None,
-                    module: &shader,
-                    entry_point: &cchar_as_string(entry_point).expect("ERROR : No code_entry_point field is set , it must be name of function which your kernel code starts from") ,
-                    compilation_options: Default::default(),
-                    cache: None,
-                });
+        let code = cchar_as_string(code)
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : No computing kernel code provided , code field is not set ."))?;
+        let entry_point = cchar_as_string(entry_point)
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : No code_entry_point field is set , it must be name of function which your kernel code starts from"))?;
 
-                let arc_compute_pipe = Arc::new(compute_pipeline);
-
-                match &gpu_data[gpu_res_index].res {
-                    None => {
-                        gpu_data[gpu_res_index].res = Some(Arc::new(Mutex::new(Vec::new())));
-                        let arci = gpu_data[gpu_res_index].res.clone().unwrap();
-                        let mut gpu_device_res = arci.lock().unwrap();
-                        let index = gpu_device_res.len();
-                        gpu_device_res.push(GPUDeviceCollection{
-                            compute_pipeline : Arc::clone(&arc_compute_pipe) ,
-                        });
-
-                        return index;
-                    },
-                    Some(arci) => {
-                        let mut gpu_device_res = arci.lock().unwrap();
-                        let index = gpu_device_res.len();
-                        gpu_device_res.push(GPUDeviceCollection{
-                            compute_pipeline : Arc::clone(&arc_compute_pipe) ,
-                        });
-
-                        return index;
-                    }
-                }
+        let constants : &[GPUOverrideConstant] = if constants_len == 0 {
+            &[]
+        } else {
+            unsafe {
+                std::slice::from_raw_parts(constants , constants_len)
             }
+        };
+        let constants = constants.iter()
+            .map(|constant| {
+                let name = cchar_as_string(constant.name)
+                    .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : name field of GPUOverrideConstant is NULL"))?;
+                Ok((name , constant.value))
+            })
+            .collect::<Result<Vec<(String , f64)> , GPUComputingError>>()?;
+        let constants : Vec<(&str , f64)> = constants.iter().map(|(name , value)| (name.as_str() , *value)).collect();
+
+        add_kernel(context , kernel_code , |real_context| Kernel::with_constants(real_context , &code , &entry_point , &constants))
+    })
+}
+
+#[no_mangle]
+// SAFETY : spirv must point to spirv_len readable bytes , entry_point must be NULL or NUL terminated and kernel_code must point to writable EMKernel pointer
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 registers SPIR-V binary module with spirv_len bytes
+/// as kernel code , so compute shaders of other toolchains can be used
+/// without porting them to wgsl . entry_point is the name of its
+/// OpEntryPoint with GLCompute execution model . the module is
+/// translated by naga , so it works on every backend
+pub extern "C" fn emcompute_register_kernel_spirv(context : *mut EMContext , spirv : *const u8 , spirv_len : usize , entry_point : *const c_char , kernel_code : *mut *mut EMKernel) -> GPUComputingStatus {
+    ffi_call(|| {
+        if kernel_code.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL kernel_code provided for emcompute_register_kernel_spirv"));
+        }
+        if spirv.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL spirv provided for emcompute_register_kernel_spirv"));
+        }
+
+        let spirv : &[u8] = unsafe {
+            std::slice::from_raw_parts(spirv , spirv_len)
+        };
+        let entry_point = cchar_as_string(entry_point)
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : No entry_point is set , it must be name of OpEntryPoint of the SPIR-V module"))?;
+
+        add_kernel(context , kernel_code , |real_context| Kernel::from_spirv(real_context , spirv , &entry_point))
+    })
+}
+
+#[repr(C)]
+/// since v7.0.0 one #define of GPU side code , name is the
+/// macro name and value is its replacement which can be NULL
+/// for macros which are only checked with #ifdef
+pub struct GPUShaderDefine {
+    pub name : *const c_char ,
+    pub value : *const c_char ,
+}
+
+#[no_mangle]
+/// since v7.0.0 registers GLSL compute shader as kernel code , defines
+/// is array of defines_len GPUShaderDefine which are set before the code
+/// like #define lines , it can be NULL when defines_len is 0 . entry
+/// point of GLSL shaders is always main function . the code is
+/// translated by naga , so it works on every backend
+pub extern "C" fn emcompute_register_kernel_glsl(context : *mut EMContext , code : *const c_char , defines : *const GPUShaderDefine , defines_len : usize , kernel_code : *mut *mut EMKernel) -> GPUComputingStatus {
+    ffi_call(|| {
+        if kernel_code.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL kernel_code provided for emcompute_register_kernel_glsl"));
         }
+        if defines.is_null() && defines_len != 0 {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL defines provided for emcompute_register_kernel_glsl with non zero defines_len"));
+        }
+
+        let code = cchar_as_string(code)
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : No computing kernel code provided , code field is not set ."))?;
+        let defines = shader_defines(defines , defines_len)?;
+        let defines : Vec<(&str , &str)> = defines.iter().map(|(name , value)| (name.as_str() , value.as_str())).collect();
+
+        add_kernel(context , kernel_code , |real_context| Kernel::from_glsl(real_context , &code , &defines))
+    })
+}
+
+#[no_mangle]
+/// since v7.0.0 stores code as source snippet of context with name ,
+/// so kernel codes which are registered with
+/// emcompute_register_kernel_preprocessed can paste it with
+/// #include "name" . snippet with the same name is replaced
+pub extern "C" fn emcompute_register_source_snippet(context : *mut EMContext , name : *const c_char , code : *const c_char) -> GPUComputingStatus {
+    ffi_call(|| {
+        let name = cchar_as_string(name)
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL name provided for emcompute_register_source_snippet"))?;
+        let code = cchar_as_string(code)
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL code provided for emcompute_register_source_snippet"))?;
+
+        with_gpu_collection(context , |collection| {
+            collection.preprocessor.add_snippet(&name , &code);
+            Ok(())
+        })
+    })
+}
+
+#[no_mangle]
+/// since v7.0.0 works like register_computing_kernel_code but code is
+/// preprocessed first , so variants of one kernel code can be registered
+/// without pasting strings together . lines of code which start with #
+/// are directives : #define NAME value , #undef NAME , #ifdef NAME ,
+/// #ifndef NAME , #else , #endif and #include "name" which pastes the
+/// snippet of emcompute_register_source_snippet . defined names are
+/// replaced with their values in the rest of the code .
+/// defines is array of defines_len GPUShaderDefine which are set before
+/// the code , it can be NULL when defines_len is 0 . problems of the
+/// directives are returned as ShaderCompileError with diagnostics .
+/// lines , columns and snippets of the diagnostics of the kernel code and
+/// emcompute_last_error point to code or the snippet which the problem is in
+pub extern "C" fn emcompute_register_kernel_preprocessed(context : *mut EMContext , code : *const c_char , entry_point : *const c_char , defines : *const GPUShaderDefine , defines_len : usize , kernel_code : *mut *mut EMKernel) -> GPUComputingStatus {
+    ffi_call(|| {
+        if kernel_code.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL kernel_code provided for emcompute_register_kernel_preprocessed"));
+        }
+        if defines.is_null() && defines_len != 0 {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL defines provided for emcompute_register_kernel_preprocessed with non zero defines_len"));
+        }
+
+        let code = cchar_as_string(code)
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : No computing kernel code provided , code field is not set ."))?;
+        let entry_point = cchar_as_string(entry_point)
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : No code_entry_point field is set , it must be name of function which your kernel code starts from"))?;
+        let defines = shader_defines(defines , defines_len)?;
+        let defines : Vec<(&str , &str)> = defines.iter().map(|(name , value)| (name.as_str() , value.as_str())).collect();
+
+        let preprocessor = with_gpu_collection(context , |collection| Ok(collection.preprocessor.clone()))?;
+        let preprocessed = preprocessor.process_with_lines(&code , &defines)?;
+
+        // diagnostics point to lines of code and the snippets
+        add_kernel(context , kernel_code , |real_context| {
+            Kernel::new(real_context , &preprocessed.code , &entry_point)
+                .map_err(|err| preprocessed.map_error(err))
+        })
+    })
+}
+
+// copies defines_len GPUShaderDefine of defines to Rust side
+fn shader_defines(defines : *const GPUShaderDefine , defines_len : usize) -> Result<Vec<(String , String)> , GPUComputingError> {
+    if defines_len == 0 {
+        return Ok(Vec::new());
     }
+
+    let defines : &[GPUShaderDefine] = unsafe {
+        std::slice::from_raw_parts(defines , defines_len)
+    };
+
+    defines.iter()
+        .map(|define| {
+            let name = cchar_as_string(define.name)
+                .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : name field of GPUShaderDefine is NULL"))?;
+            Ok((name , cchar_as_string(define.value).unwrap_or_default()))
+        })
+        .collect()
+}
+
+#[no_mangle]
+/// since v7.0.0 frees compute pipeline of the kernel code , the handle
+/// can not be used anymore but other kernel codes of its context keep
+/// working . memory of the handle can be reused by next registered 
+/// kernel codes , but the old handle stays invalid
+pub extern "C" fn emcompute_kernel_destroy(kernel_code : *mut EMKernel) -> GPUComputingStatus {
+    ffi_call(|| {
+        GPU_KERNELS.lock().unwrap()
+            .remove(from_ptr(kernel_code))
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidHandle , "ERROR : invalid or already destroyed EMKernel provided for emcompute_kernel_destroy"))?;
+
+        Ok(())
+    })
 }
 
 #[no_mangle]
 /// when your work fully finished with kernel codes and you 
 /// wont need to use them anymore , you can use this 
-/// function to cleanup all the mess which they created from memory
-pub extern "C" fn free_compute_kernel_codes(gpu_res_index : usize){
-    unsafe {
-        match &GPU_RES_KEEPER {
-            None => return ,
-            Some(arci) => {
-                let mut gpu_data = arci.lock().unwrap();
-                if gpu_data.len() < gpu_res_index {
-                    return;
-                }else{
-                    gpu_data[gpu_res_index].res = None;
-                    return;
-                }
-            }
-        }
-    }
+/// function to cleanup all the mess which they created from memory .
+/// since v7.0.0 it destroys every kernel code registered on context ,
+/// for retiring only one of them use emcompute_kernel_destroy
+pub extern "C" fn free_compute_kernel_codes(context : *mut EMContext) -> GPUComputingStatus {
+    ffi_call(|| {
+        with_gpu_collection(context , |_| Ok(()))?;
+
+        let handle = from_ptr(context);
+        GPU_KERNELS.lock().unwrap()
+            .remove_where(|kernel| kernel.context == handle);
+
+        Ok(())
+    })
 }
 
 
@@ -357,86 +713,143 @@ pub struct CKernel {
     /// set max number of workgroups in z dimension
     pub z : u32 ,
     /// since v4.0.0 instead of directly passing 
-    /// kernel code , you have to pass return 
-    /// value of register_computing_kernel_code
+    /// kernel code , you have to pass handle 
+    /// which register_computing_kernel_code writes
     /// to this field 
-    pub kernel_code_index : usize ,
+    pub kernel_code : *mut EMKernel ,
     /// since v4.0.0 instead of directly passing 
     /// configs of your computing task 
-    /// you have to create_computing_gpu_resources
-    /// return value to this field
-    pub config_index : usize ,
+    /// you have to pass handle which 
+    /// create_computing_gpu_resources writes to this field
+    pub context : *mut EMContext ,
 }
 
 #[no_mangle]
+// SAFETY : kernel must point to writable CKernel
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 /// because setting CKernel config can be annoying if you just 
 /// want to do simple task , this function provides general 
 /// config which will meet most of your needs . since v4.0.0 
 /// this function calls create_computing_gpu_resources automatically
-/// and assign its return value to config_index of your CKernel variable .
+/// and assign its handle to context field of your CKernel variable .
 /// only use this function once in your programs , instead of using this 
-/// many times and causing memory leaks (well all that mem can be freed by free_compute_cache function)
-/// use config_index field of CKernel variable 
-pub extern "C" fn set_kernel_default_config(kernel: *mut CKernel) -> usize{
-    // println!("set start"); 
-    if kernel.is_null() {
-        panic!("ERROR : NULL value provided for set_kernel_default_config");
-    }
+/// many times and causing memory leaks (well all that mem can be freed by 
+/// emcompute_context_destroy or free_compute_cache function)
+/// use context field of CKernel variable .
+/// since v7.0.0 it returns GPUComputingStatus , the handle is only 
+/// available through context field of your CKernel variable
+pub extern "C" fn set_kernel_default_config(kernel: *mut CKernel) -> GPUComputingStatus {
+    ffi_call(|| {
+        // println!("set start"); 
+        if kernel.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL value provided for set_kernel_default_config"));
+        }
 
-    unsafe {
+        let kernel = unsafe { &mut *kernel };
 
-        let kernel = &mut *kernel;
+        kernel.context = add_gpu_collection(Context::from_default_config()?)?;
 
+        Ok(())
+    })
+}
 
-        let config = GPUComputingConfig {
-            backend: GPUComputingBackend::opengl,
-            power: GPUPowerSettings::HighPerformance,
-            speed: GPUSpeedSettings::low_speed,
-            memory: GPUMemorySettings::prefer_memory,
-            gpu_index_in_backend_group : -1,
-        };
+#[no_mangle]
+// SAFETY : kernel must point to writable CKernel
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 instead of computing x , y and z fields of kernel by hand ,
+/// this function reads @workgroup_size of the registered kernel code and sets
+/// them so size_x * size_y * size_z elements are covered (for 1D problems pass
+/// 1 as size_y and size_z) . when a count is bigger than
+/// max_compute_workgroups_per_dimension of the gpu device it is split and the
+/// rest is moved to the next dimension , so kernel codes which can receive such
+/// sizes must compute their element index from @builtin(num_workgroups) .
+/// kernel codes must skip the indices which are out of the size , because the
+/// last workgroups can be partially used
+pub extern "C" fn emcompute_set_workgroups_for_size(kernel : *mut CKernel , size_x : u32 , size_y : u32 , size_z : u32) -> GPUComputingStatus {
+    ffi_call(|| {
+        if kernel.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL value provided for emcompute_set_workgroups_for_size"));
+        }
 
-        let customize = GPUCustomSettings::default();
+        let kernel = unsafe { &mut *kernel };
 
-        let index = create_computing_gpu_resources(config , customize);
+        let (_ , real_kernel) = kernel.get_real_config()?;
+        let [x , y , z] = real_kernel.workgroups_for_size([size_x , size_y , size_z])?;
 
-        kernel.config_index = index;
+        kernel.x = x;
+        kernel.y = y;
+        kernel.z = z;
 
-        return index;
-    }
+        Ok(())
+    })
 }
 
 
 impl CKernel {
-    // this function converts enums to
+    // this function converts handles to
     // equivalent gpu resources
-    fn get_real_config(&self) -> (Arc<wgpu::Device> , Arc<wgpu::Queue> , Arc<wgpu::ComputePipeline>) {
-        unsafe{
-            match &GPU_RES_KEEPER {
-                None => {
-                    panic!("ERROR : before using compute function you must use create_gpu_resources and register_kernel_code");
-                },
-                Some(arci) => {
-                    let gpu_data = arci.lock().unwrap();
-                    if gpu_data.len() <= self.config_index {
-                        panic!("ERROR : invalid config_index used for CKernel arg");
-                    }
-                    if let Some(arcii) = &gpu_data[self.config_index].res {
-                        let gpu_device_data = arcii.lock().unwrap();
-                        if gpu_device_data.len() <= self.kernel_code_index {
-                            panic!("ERROR : invalid kernel_code_index used for CKernel arg");
-                        }
-
-                        (Arc::clone(&gpu_data[self.config_index].device) , Arc::clone(&gpu_data[self.config_index].queue) , Arc::clone(&gpu_device_data[self.kernel_code_index].compute_pipeline))
-                    }else{
-                        panic!("ERROR : before using compute function you must register_kernel_code");
-                    }
-                },
-            }
+    fn get_real_config(&self) -> Result<(Context , Kernel) , GPUComputingError> {
+        let context = with_gpu_collection(self.context , |collection| Ok(collection.context.clone()))?;
+
+        let kernels = GPU_KERNELS.lock().unwrap();
+        match kernels.get(from_ptr(self.kernel_code)) {
+            None => Err(GPUComputingError::new(GPUComputingStatus::InvalidHandle , "ERROR : invalid or destroyed kernel_code used for CKernel arg , you must register_computing_kernel_code before using compute function")),
+            Some(kernel) => Ok((context , kernel.kernel.clone())),
         }
     }
 }
 
+#[repr(C)]
+#[derive(Debug, Clone , Copy , PartialEq , Eq)]
+/// since v7.0.0 this enum tells to API how your kernel 
+/// code uses data of DataBinder , so copies which are not 
+/// needed can be skipped
+pub enum GPUDataAccess {
+    /// data is uploaded to gpu side and read back after computing
+    ReadWrite = 0 ,
+    /// data is only input , it is uploaded but never read back
+    ReadOnly = 1 ,
+    /// data is only output , it is not uploaded , gpu side buffer 
+    /// is zero initialized and read back after computing
+    WriteOnly = 2 ,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone , Copy , PartialEq , Eq)]
+/// since v7.0.0 this enum tells to API where 
+/// results of the DataBinder must be written
+pub enum GPUReadBackMode {
+    /// results are copied to the memory which data field 
+    /// points to , memory stays owned by you
+    InPlace = 0 ,
+    /// API allocates new memory for results and writes its 
+    /// address to data field , your old memory is not touched .
+    /// the new memory must be freed with emcompute_free_bytes 
+    NewAllocation = 1 ,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone , Copy , PartialEq , Eq)]
+/// since v7.0.0 this enum tells to API which kind 
+/// of variable the DataBinder is bound to in kernel code
+pub enum GPUBindingKind {
+    /// var<storage> , data is used based on access field
+    Storage = 0 ,
+    /// var<uniform> for small parameters , data is uploaded 
+    /// into uniform buffer and never read back , access and 
+    /// read_back_mode fields are ignored
+    Uniform = 1 ,
+    /// texture_storage_2d or texture_storage_3d , texture field 
+    /// describes data and it is used based on access field
+    /// (read_write storage textures are not supported by all gpu devices)
+    StorageTexture = 2 ,
+    /// texture_2d or texture_3d which kernel code can sample , texture 
+    /// field describes data , it is uploaded and never read back
+    SampledTexture = 3 ,
+    /// sampler , sampler field describes it and data field is ignored
+    Sampler = 4 ,
+}
+
 #[repr(C)]
 #[derive(Debug, Clone)]
 /// this struct is for passing
@@ -455,6 +868,33 @@ pub struct DataBinder {
     /// in gpu side the type of this data will 
     /// be set based on CKernel code you provided
     pub data: *mut *mut u8,
+    /// since v7.0.0 if it is set to non negative value , the computing 
+    /// buffer with this index (returned by create_computing_buffer) is 
+    /// bound instead of data , data and data_len are ignored and results 
+    /// stay on gpu side , use read_computing_buffer to get them .
+    /// if it sets to negative value , data field is used
+    pub buffer_index: i64,
+    /// since v7.0.0 access mode of data field , it is ignored 
+    /// when buffer_index is used
+    pub access: GPUDataAccess,
+    /// since v7.0.0 results are copied to the memory which data 
+    /// points to by default (InPlace) , it is ignored when 
+    /// buffer_index is used
+    pub read_back_mode: GPUReadBackMode,
+    /// since v7.0.0 kind of the variable in kernel code , it is 
+    /// ignored when buffer_index is used because computing buffers 
+    /// can be bound to both var<storage> and var<uniform>
+    pub kind: GPUBindingKind,
+    /// since v7.0.0 format and size of data when kind is 
+    /// StorageTexture or SampledTexture , data_len must be 
+    /// width * height * depth * texel size of the format
+    pub texture: GPUTextureInfo,
+    /// since v7.0.0 settings of the sampler when kind is Sampler
+    pub sampler: GPUSamplerInfo,
+    /// since v7.0.0 it is only used by emcompute_multi_device_compute ,
+    /// when it is true data is divided between the devices like the
+    /// range , otherwise every device gets the whole data
+    pub split: bool,
 }
 
 #[repr(C)]
@@ -526,7 +966,7 @@ pub struct GPUSpeedCustom {
 }
 
 impl GPUSpeedCustom {
-    fn to_gpu_limits(&self) -> wgpu::Limits {
+    pub(crate) fn to_gpu_limits(&self) -> wgpu::Limits {
         wgpu::Limits {
             max_texture_dimension_1d: self.max_texture_dimension_1d,
             max_texture_dimension_2d: self.max_texture_dimension_2d,
@@ -564,6 +1004,96 @@ impl GPUSpeedCustom {
             max_non_sampler_bindings: self.max_non_sampler_bindings,
         }
     }
+
+    // zero fields are taken from the default limits and every
+    // limit is clamped to what the adapter supports
+    pub(crate) fn to_clamped_gpu_limits(&self , supported : &wgpu::Limits) -> wgpu::Limits {
+        let mut limits = wgpu::Limits::default();
+
+        // max limits can not be higher than the supported ones
+        // and min limits (alignments) can not be lower
+        macro_rules! clamp {
+            (max : $($max:ident),* ; min : $($min:ident),*) => {
+                $(
+                    if self.$max != 0 {
+                        limits.$max = self.$max;
+                    }
+                    limits.$max = limits.$max.min(supported.$max);
+                )*
+                $(
+                    if self.$min != 0 {
+                        limits.$min = self.$min;
+                    }
+                    limits.$min = limits.$min.max(supported.$min);
+                )*
+            };
+        }
+
+        clamp!(
+            max :
+            max_texture_dimension_1d , max_texture_dimension_2d , max_texture_dimension_3d ,
+            max_texture_array_layers , max_bind_groups , max_bindings_per_bind_group ,
+            max_dynamic_uniform_buffers_per_pipeline_layout ,
+            max_dynamic_storage_buffers_per_pipeline_layout ,
+            max_sampled_textures_per_shader_stage , max_samplers_per_shader_stage ,
+            max_storage_buffers_per_shader_stage , max_storage_textures_per_shader_stage ,
+            max_uniform_buffers_per_shader_stage , max_uniform_buffer_binding_size ,
+            max_storage_buffer_binding_size , max_vertex_buffers , max_buffer_size ,
+            max_vertex_attributes , max_vertex_buffer_array_stride ,
+            max_inter_stage_shader_components , max_color_attachments ,
+            max_color_attachment_bytes_per_sample , max_compute_workgroup_storage_size ,
+            max_compute_invocations_per_workgroup , max_compute_workgroup_size_x ,
+            max_compute_workgroup_size_y , max_compute_workgroup_size_z ,
+            max_compute_workgroups_per_dimension , max_subgroup_size , max_push_constant_size ,
+            max_non_sampler_bindings ;
+            min :
+            min_uniform_buffer_offset_alignment , min_storage_buffer_offset_alignment ,
+            min_subgroup_size
+        );
+
+        limits
+    }
+}
+
+impl From<wgpu::Limits> for GPUSpeedCustom {
+    fn from(limits : wgpu::Limits) -> Self {
+        GPUSpeedCustom {
+            max_texture_dimension_1d: limits.max_texture_dimension_1d,
+            max_texture_dimension_2d: limits.max_texture_dimension_2d,
+            max_texture_dimension_3d: limits.max_texture_dimension_3d,
+            max_texture_array_layers: limits.max_texture_array_layers,
+            max_bind_groups: limits.max_bind_groups,
+            max_bindings_per_bind_group: limits.max_bindings_per_bind_group,
+            max_dynamic_uniform_buffers_per_pipeline_layout: limits.max_dynamic_uniform_buffers_per_pipeline_layout,
+            max_dynamic_storage_buffers_per_pipeline_layout: limits.max_dynamic_storage_buffers_per_pipeline_layout,
+            max_sampled_textures_per_shader_stage: limits.max_sampled_textures_per_shader_stage,
+            max_samplers_per_shader_stage: limits.max_samplers_per_shader_stage,
+            max_storage_buffers_per_shader_stage: limits.max_storage_buffers_per_shader_stage,
+            max_storage_textures_per_shader_stage: limits.max_storage_textures_per_shader_stage,
+            max_uniform_buffers_per_shader_stage: limits.max_uniform_buffers_per_shader_stage,
+            max_uniform_buffer_binding_size: limits.max_uniform_buffer_binding_size,
+            max_storage_buffer_binding_size: limits.max_storage_buffer_binding_size,
+            max_vertex_buffers: limits.max_vertex_buffers,
+            max_buffer_size: limits.max_buffer_size,
+            max_vertex_attributes: limits.max_vertex_attributes,
+            max_vertex_buffer_array_stride: limits.max_vertex_buffer_array_stride,
+            min_uniform_buffer_offset_alignment: limits.min_uniform_buffer_offset_alignment,
+            min_storage_buffer_offset_alignment: limits.min_storage_buffer_offset_alignment,
+            max_inter_stage_shader_components: limits.max_inter_stage_shader_components,
+            max_color_attachments: limits.max_color_attachments,
+            max_color_attachment_bytes_per_sample: limits.max_color_attachment_bytes_per_sample,
+            max_compute_workgroup_storage_size: limits.max_compute_workgroup_storage_size,
+            max_compute_invocations_per_workgroup: limits.max_compute_invocations_per_workgroup,
+            max_compute_workgroup_size_x: limits.max_compute_workgroup_size_x,
+            max_compute_workgroup_size_y: limits.max_compute_workgroup_size_y,
+            max_compute_workgroup_size_z: limits.max_compute_workgroup_size_z,
+            max_compute_workgroups_per_dimension: limits.max_compute_workgroups_per_dimension,
+            min_subgroup_size: limits.min_subgroup_size,
+            max_subgroup_size: limits.max_subgroup_size,
+            max_push_constant_size: limits.max_push_constant_size,
+            max_non_sampler_bindings: limits.max_non_sampler_bindings,
+        }
+    }
 }
 
 /*
@@ -597,6 +1127,18 @@ pub struct GroupOfBinders {
     pub datas_len : usize ,
 }
 
+impl GroupOfBinders {
+    fn binders(&self) -> Result<&[DataBinder] , GPUComputingError> {
+        if self.datas.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : no data provided for datas field of group {} in data_for_gpu arg" , self.group)));
+        }
+
+        Ok(unsafe {
+            std::slice::from_raw_parts(self.datas , self.datas_len)
+        })
+    }
+}
+
 
 
 #[no_mangle]
@@ -610,184 +1152,231 @@ pub struct GroupOfBinders {
 /// unlike CUDA , you dont need to copy data to gpu manually , this function does it for you 
 /// in the most performant possible way 
 ///
+/// since v7.0.0 it returns GPUComputingStatus instead of -1 or panicking
+///
+/// since v7.0.0 data_for_gpu is checked against the @group @binding layout
+/// of the kernel code before dispatching , mismatches are returned as
+/// InvalidArgument naming the group , binding and expected size
+///
 /// if you find any bug or any problem , help us to fix it -> https://github.com/SkillfulElectro/EMCompute.git
-pub extern "C" fn compute(kernel : *mut CKernel , data_for_gpu : *mut GroupOfBinders , gpu_data_len : usize) -> i32 {
-
-    {
-        // println!("compute start");
-        //
-        let kernel = unsafe {&mut *kernel};
-
-
+pub extern "C" fn compute(kernel : *mut CKernel , data_for_gpu : *mut GroupOfBinders , gpu_data_len : usize) -> GPUComputingStatus {
+    ffi_call(|| compute_groups(kernel , data_for_gpu , gpu_data_len , None))
+}
 
-        if data_for_gpu.is_null(){
-            println!("ERROR : data_for_gpu arg of compute function is NULL");
-            return -1;
+#[no_mangle]
+// SAFETY : kernel must point to CKernel , data_for_gpu to gpu_data_len GroupOfBinders with valid DataBinders and push_constants to push_constants_len readable bytes
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 works like compute function and sets push_constants_len
+/// bytes of push_constants to var<push_constant> of the kernel code before
+/// dispatching . push_constants field of GPUComputingConfig must be true and
+/// the gpu device must support push constants , otherwise UnsupportedFeature
+/// is returned . push_constants_len must be multiple of 4
+pub extern "C" fn compute_with_push_constants(kernel : *mut CKernel , data_for_gpu : *mut GroupOfBinders , gpu_data_len : usize , push_constants : *const u8 , push_constants_len : usize) -> GPUComputingStatus {
+    ffi_call(|| {
+        if push_constants.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : push_constants arg of compute_with_push_constants function is NULL"));
         }
 
-        let (device , queue , compute_pipeline) = kernel.get_real_config();
-
-        // println!("compute data stage");
-
-
-
-
-        let mut encoder =
-            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        let push_constants : &[u8] = unsafe {
+            std::slice::from_raw_parts(push_constants , push_constants_len)
+        };
 
-        let mut staging_buffers : Vec<wgpu::Buffer> = Vec::new();
-        let mut sizes : Vec<wgpu::BufferAddress> = Vec::new();
-        let mut storage_buffers : Vec<wgpu::Buffer> = Vec::new();
+        compute_groups(kernel , data_for_gpu , gpu_data_len , Some(push_constants))
+    })
+}
 
+// memory of NewAllocation binders and the data fields which
+// receive it after computing , it is freed when the task fails
+struct NewAllocations(Vec<(*mut *mut u8 , *mut [u8])>);
 
-        let groups : &mut [GroupOfBinders] = unsafe { std::slice::from_raw_parts_mut(data_for_gpu , gpu_data_len) };
+// data fields belong to the caller of compute_async and they
+// are only written by the thread which finishes the task
+unsafe impl Send for NewAllocations {}
 
-        // println!("before cpass");
-        {
-            let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                label: None,
-                timestamp_writes: None,
-            });
+impl NewAllocations {
+    fn append(&mut self , mut other : NewAllocations) {
+        self.0.append(&mut other.0);
+    }
 
-            for group in &mut *groups {
-                let bind_group_layout = compute_pipeline.get_bind_group_layout(group.group);
-                if group.datas.is_null() {
-                    println!("ERROR : no data provided for datas field in data_for_gpu arg");
-                    return -1;
+    fn finish(mut self , success : bool) {
+        for (data , new_data) in self.0.drain(..) {
+            unsafe {
+                if success {
+                    *data = new_data as *mut u8;
+                } else {
+                    drop(Box::from_raw(new_data));
                 }
+            }
+        }
+    }
+}
 
-                let bindings : &mut [DataBinder] = unsafe{
-                    std::slice::from_raw_parts_mut(group.datas , group.datas_len)
-                };
-
-                let mut tmp_staging_buffers : Vec<wgpu::Buffer> = Vec::new();
-                let mut tmp_sizes : Vec<wgpu::BufferAddress> = Vec::new();
-                let mut tmp_storage_buffers : Vec<wgpu::Buffer> = Vec::new();
+impl Drop for NewAllocations {
+    fn drop(&mut self) {
+        for (_ , new_data) in self.0.drain(..) {
+            unsafe {
+                drop(Box::from_raw(new_data));
+            }
+        }
+    }
+}
 
-                let mut entries : Vec<wgpu::BindGroupEntry> = Vec::new();
+// computing task which is translated from C side arguments
+struct ComputeTask {
+    context : Context ,
+    // EMContext handle which context is reached by
+    context_handle : usize ,
+    kernel : Kernel ,
+    workgroups : [u32; 3] ,
+    bindings : Vec<Binding<'static>> ,
+    new_allocations : NewAllocations ,
+}
 
-                for binder in &mut *bindings {
-                    if binder.data.is_null() {
-                        println!("ERROR : null data field in DataBinder found");
-                        return -1;
-                    }
+// translates GroupOfBinders to Bindings , host memory of DataBinders
+// must stay valid until the task is finished
+fn translate_groups(kernel : *mut CKernel , data_for_gpu : *mut GroupOfBinders , gpu_data_len : usize) -> Result<ComputeTask , GPUComputingError> {
+    // println!("compute start");
+    //
+    if kernel.is_null() {
+        return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : kernel arg of compute function is NULL"));
+    }
 
-                    let data : &[u8] = unsafe{
-                        std::slice::from_raw_parts(*binder.data , binder.data_len)
-                    };
+    let kernel = unsafe {&*kernel};
 
 
 
-                    let size = std::mem::size_of_val(data) as wgpu::BufferAddress;
+    if data_for_gpu.is_null(){
+        return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : data_for_gpu arg of compute function is NULL"));
+    }
 
-                    let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-                        label: None ,
-                        size : size ,
-                        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
-                        mapped_at_creation: false,
-                    });
+    let (context , real_kernel) = kernel.get_real_config()?;
 
-                    let storage_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                        label: Some("Storage Buffer"),
-                        contents: data ,
-                        usage: wgpu::BufferUsages::STORAGE
-                            | wgpu::BufferUsages::COPY_DST
-                            | wgpu::BufferUsages::COPY_SRC,
-                    });
+    // println!("compute data stage");
 
+    let groups : &[GroupOfBinders] = unsafe { std::slice::from_raw_parts(data_for_gpu , gpu_data_len) };
+    let (bindings , new_allocations) = translate_binders(kernel.context , groups)?;
 
+    Ok(ComputeTask {
+        context ,
+        context_handle : from_ptr(kernel.context) ,
+        kernel : real_kernel ,
+        workgroups : [kernel.x , kernel.y , kernel.z] ,
+        bindings ,
+        new_allocations ,
+    })
+}
 
-                    tmp_sizes.push(size);
-                    tmp_staging_buffers.push(staging_buffer);
-                    tmp_storage_buffers.push(storage_buffer);
-                }
+// translates DataBinders of groups to Bindings , computing buffers
+// (buffer_index) are looked up on the EMContext of context handle
+fn translate_binders(context : *mut EMContext , groups : &[GroupOfBinders]) -> Result<(Vec<Binding<'static>> , NewAllocations) , GPUComputingError> {
+    // first pass only validates and finds computing buffers ,
+    // so nothing has to be undone when it fails
+    let mut buffer_bindings : Vec<Binding> = Vec::new();
+
+    for group in groups {
+        for binder in group.binders()? {
+            if binder.buffer_index >= 0 {
+                let buffer = gpu_buffer::get_computing_buffer(context , binder.buffer_index as usize)?;
+                buffer_bindings.push(Binding::buffer(group.group , binder.bind , &buffer.lock().unwrap()));
+            } else if binder.kind != GPUBindingKind::Sampler && (binder.data.is_null() || unsafe { (*binder.data).is_null() }) {
+                return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : null data field in DataBinder with bind index {} of group {} found" , binder.bind , group.group)));
+            }
+        }
+    }
 
+    let mut new_allocations = NewAllocations(Vec::new());
 
+    let mut buffer_bindings = buffer_bindings.into_iter();
+    let mut bindings : Vec<Binding> = Vec::new();
 
-                for (i, binder) in bindings.iter().enumerate() {
-                    entries.push(wgpu::BindGroupEntry {
-                        binding: binder.bind.clone(),
-                        resource: tmp_storage_buffers[i].as_entire_binding(),
-                    });
+    for group in groups {
+        for binder in group.binders()? {
+            if binder.buffer_index >= 0 {
+                if let Some(binding) = buffer_bindings.next() {
+                    bindings.push(binding);
                 }
-
-                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                    label: None,
-                    layout: &bind_group_layout,
-                    entries: entries.as_slice() ,
-                });
-
-                cpass.set_pipeline(&compute_pipeline);
-                cpass.set_bind_group(group.group , &bind_group, &[]);
-
-                storage_buffers.append(&mut tmp_storage_buffers);
-                staging_buffers.append(&mut tmp_staging_buffers);
-                sizes.append(&mut tmp_sizes);
+                continue;
             }
 
-            cpass.insert_debug_marker("debug_marker");
-            cpass.dispatch_workgroups(kernel.x, kernel.y, kernel.z);
-        }
-        // println!("after cpass");
-
-
-        for (index, storage_buffer) in storage_buffers.iter().enumerate() {
-            encoder.copy_buffer_to_buffer(&storage_buffer, 0, &staging_buffers[index], 0, sizes[index]);
-        }
-
-        queue.submit(Some(encoder.finish()));
+            if binder.kind == GPUBindingKind::Sampler {
+                bindings.push(Binding::sampler(group.group , binder.bind , binder.sampler));
+                continue;
+            }
 
+            let is_storage_texture = binder.kind == GPUBindingKind::StorageTexture;
 
+            if binder.kind == GPUBindingKind::Uniform || binder.kind == GPUBindingKind::SampledTexture || binder.access == GPUDataAccess::ReadOnly {
+                let data : &[u8] = unsafe{
+                    std::slice::from_raw_parts(*binder.data , binder.data_len)
+                };
+                bindings.push(match binder.kind {
+                    GPUBindingKind::Uniform => Binding::uniform(group.group , binder.bind , data) ,
+                    GPUBindingKind::SampledTexture => Binding::sampled_texture(group.group , binder.bind , binder.texture , data) ,
+                    GPUBindingKind::StorageTexture => Binding::storage_texture_input(group.group , binder.bind , binder.texture , data) ,
+                    _ => Binding::input(group.group , binder.bind , data) ,
+                });
+                continue;
+            }
 
-        let mut index : usize = 0;
-        for group in groups {
-            let bindings : &mut [DataBinder] = unsafe{
-                std::slice::from_raw_parts_mut(group.datas , group.datas_len)
+            let mut data : &mut [u8] = unsafe{
+                std::slice::from_raw_parts_mut(*binder.data , binder.data_len)
             };
 
-            for binder in bindings {
-
-                let data : Box<[u8]> = unsafe{
-                    Box::from_raw(std::slice::from_raw_parts_mut(*binder.data , binder.data_len))
+            if binder.read_back_mode == GPUReadBackMode::NewAllocation {
+                let new_data : Box<[u8]> = match binder.access {
+                    GPUDataAccess::WriteOnly => vec![0u8; binder.data_len].into_boxed_slice() ,
+                    _ => (&*data).into() ,
                 };
+                let new_data = Box::into_raw(new_data);
+                new_allocations.0.push((binder.data , new_data));
+                data = unsafe { &mut *new_data };
+            }
 
+            bindings.push(match (is_storage_texture , binder.access) {
+                (true , GPUDataAccess::WriteOnly) => Binding::storage_texture_output(group.group , binder.bind , binder.texture , data) ,
+                (true , _) => Binding::storage_texture(group.group , binder.bind , binder.texture , data) ,
+                (false , GPUDataAccess::WriteOnly) => Binding::output(group.group , binder.bind , data) ,
+                (false , _) => Binding::read_write(group.group , binder.bind , data) ,
+            });
+        }
+    }
 
-                let buffer_slice = staging_buffers[index].slice(..);
-                let (sender, receiver) = flume::bounded(1);
-                buffer_slice.map_async(wgpu::MapMode::Read, move |v| sender.send(v).unwrap());
-
-                device.poll(wgpu::Maintain::wait()).panic_on_timeout();
-
-                if let Ok(Ok(())) = pollster::block_on(receiver.recv_async()) {
-                    let mapped_data = buffer_slice.get_mapped_range();
-
-
+    Ok((bindings , new_allocations))
+}
 
-                    unsafe {
-                        let mapped_data_ptr = mapped_data.as_ptr();
-                        let data: &[u8] = unsafe { std::slice::from_raw_parts(mapped_data_ptr , binder.data_len) };
-                        let tmp_box : Box<[u8]> = data.into();
-                        *binder.data = tmp_box.as_ptr() as *mut u8;
-                        std::mem::forget(tmp_box);
-                    }
+// translates GroupOfBinders to Bindings and runs the kernel
+fn compute_groups(kernel : *mut CKernel , data_for_gpu : *mut GroupOfBinders , gpu_data_len : usize , push_constants : Option<&[u8]>) -> Result<() , GPUComputingError> {
+    let ComputeTask { context , kernel , workgroups , mut bindings , new_allocations , .. } = translate_groups(kernel , data_for_gpu , gpu_data_len)?;
 
+    let result = match push_constants {
+        None => context.compute(&kernel , workgroups , &mut bindings) ,
+        Some(push_constants) => context.compute_with_push_constants(&kernel , workgroups , &mut bindings , push_constants) ,
+    };
+    drop(bindings);
 
+    new_allocations.finish(result.is_ok());
 
-                    drop(mapped_data);
-                    staging_buffers[index].unmap();
+    result
+}
 
-                } else {
-                    panic!("failed to run compute on gpu!")
-                }
 
-                index += 1;
-            }
+#[no_mangle]
+/// since v7.0.0 memory which API allocated for you (for example 
+/// results of DataBinder with NewAllocation read_back_mode) must 
+/// be freed with this function instead of free , len is the 
+/// data_len of that memory
+pub extern "C" fn emcompute_free_bytes(data : *mut u8 , len : usize) -> GPUComputingStatus {
+    ffi_call(|| {
+        if data.is_null() {
+            return Ok(());
         }
 
+        unsafe {
+            drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(data , len)));
+        }
 
-
-        return 0;
-    }
+        Ok(())
+    })
 }
 
 
@@ -798,8 +1387,46 @@ pub extern "C" fn compute(kernel : *mut CKernel , data_for_gpu : *mut GroupOfBin
 /// automatically , but in some cases 
 /// you might want to do it manually
 /// so just call free_compute_cache();
-pub extern "C" fn free_compute_cache(){
-    unsafe {
-        GPU_RES_KEEPER = None;
+/// since v7.0.0 it destroys every EMContext , EMKernel , EMJob , 
+/// EMCommandList and EMMultiDevice handle , use emcompute_context_destroy 
+/// for freeing only one of them
+pub extern "C" fn free_compute_cache() -> GPUComputingStatus {
+    ffi_call(|| {
+        gpu_multi_device::GPU_MULTI_DEVICES.lock().unwrap().remove_where(|_| true);
+        gpu_command_list::GPU_COMMAND_LISTS.lock().unwrap().remove_where(|_| true);
+        gpu_job::GPU_JOBS.lock().unwrap().remove_where(|_| true);
+        GPU_KERNELS.lock().unwrap().remove_where(|_| true);
+        GPU_CONTEXTS.lock().unwrap().remove_where(|_| true);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_limits_are_clamped_to_supported_limits() {
+        let supported = wgpu::Limits::downlevel_defaults();
+
+        let mut custom = GPUSpeedCustom::from(supported.clone());
+        // above the supported max , kept below it , left to the default
+        custom.max_buffer_size = supported.max_buffer_size + 1;
+        custom.max_bind_groups = supported.max_bind_groups - 1;
+        custom.max_compute_workgroups_per_dimension = 0;
+        // below the supported min alignment , kept above it
+        custom.min_uniform_buffer_offset_alignment = supported.min_uniform_buffer_offset_alignment / 2;
+        custom.min_storage_buffer_offset_alignment = supported.min_storage_buffer_offset_alignment * 2;
+
+        let limits = custom.to_clamped_gpu_limits(&supported);
+
+        assert_eq!(limits.max_buffer_size , supported.max_buffer_size);
+        assert_eq!(limits.max_bind_groups , supported.max_bind_groups - 1);
+        assert_eq!(
+            limits.max_compute_workgroups_per_dimension ,
+            wgpu::Limits::default().max_compute_workgroups_per_dimension.min(supported.max_compute_workgroups_per_dimension)
+        );
+        assert_eq!(limits.min_uniform_buffer_offset_alignment , supported.min_uniform_buffer_offset_alignment);
+        assert_eq!(limits.min_storage_buffer_offset_alignment , supported.min_storage_buffer_offset_alignment * 2);
     }
 }
@@ -0,0 +1,420 @@
+use std::ops::Range;
+use std::time::{Duration , Instant};
+
+use crate::context::Context;
+use crate::kernel::Kernel;
+use crate::binding::{Binding , BindingResource , HostData};
+use crate::job::Job;
+use crate::error::{GPUComputingError , GPUComputingStatus};
+
+
+/// MultiBinding tells MultiDevice how a Binding is shared
+/// between its devices
+pub enum MultiBinding<'a> {
+    /// every device gets the whole binding , so it must be read
+    /// only (input , uniform , sampled texture or sampler)
+    Whole(Binding<'a>) ,
+    /// host data of Binding::input , Binding::output or
+    /// Binding::read_write is divided like the range , each device
+    /// gets the part which matches its part of the range
+    Split(Binding<'a>) ,
+}
+
+// one device of MultiDevice , throughput is items per
+// second of its last computing task
+struct Device {
+    context : Context ,
+    kernel : Kernel ,
+    weight : f64 ,
+    throughput : Option<f64> ,
+}
+
+/// MultiDevice runs one kernel code on several Contexts at once ,
+/// so machines with more than one gpu can use all of them for one task .
+/// the range is divided between the devices proportionally to their
+/// weights : 1D ranges ([len , 1]) are divided along x and 2D ranges
+/// are divided into bands of rows along y . every device runs the
+/// kernel code on its part like it is the whole range , so indices
+/// of @builtin(global_invocation_id) start from 0 on each device and
+/// set_range_binding can tell the kernel code where its part starts
+pub struct MultiDevice {
+    devices : Vec<Device> ,
+    range_binding : Option<(u32 , u32)> ,
+}
+
+impl Default for MultiDevice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MultiDevice {
+    pub fn new() -> Self {
+        MultiDevice {
+            devices : Vec::new() ,
+            range_binding : None ,
+        }
+    }
+
+    /// adds context which runs kernel , kernel must be registered on
+    /// context and it must be the same kernel code on every device , so
+    /// its bindings and workgroup size must match the first device .
+    /// weight is share of the range which the device gets compared to
+    /// weights of the other devices
+    pub fn add(&mut self , context : &Context , kernel : &Kernel , weight : f64) -> Result<() , GPUComputingError> {
+        context.check_same_device(&kernel.device)?;
+        check_weight(weight)?;
+
+        if let Some(first) = self.devices.first() {
+            let (first , added) = (first.kernel.reflection() , kernel.reflection());
+            if first.bindings != added.bindings || first.workgroup_size != added.workgroup_size {
+                return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : kernel code of the added device has other bindings or workgroup size than kernel code of the first device"));
+            }
+        }
+
+        self.devices.push(Device {
+            context : context.clone() ,
+            kernel : kernel.clone() ,
+            weight ,
+            throughput : None ,
+        });
+
+        Ok(())
+    }
+
+    /// number of added devices
+    pub fn len(&self) -> usize {
+        self.devices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+
+    /// changes weight of the device which is added at index
+    pub fn set_weight(&mut self , index : usize , weight : f64) -> Result<() , GPUComputingError> {
+        check_weight(weight)?;
+
+        let len = self.devices.len();
+        let device = self.devices.get_mut(index)
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : device index {} is out of range , {} devices are added" , index , len)))?;
+        device.weight = weight;
+
+        Ok(())
+    }
+
+    /// weights of the devices in the order they are added
+    pub fn weights(&self) -> Vec<f64> {
+        self.devices.iter()
+            .map(|device| device.weight)
+            .collect()
+    }
+
+    /// sets weights to the throughput which the devices had in the last
+    /// compute , so faster devices get bigger parts next time . devices
+    /// which had no part yet keep their weight
+    pub fn balance(&mut self) {
+        for device in &mut self.devices {
+            if let Some(throughput) = device.throughput {
+                device.weight = throughput;
+            }
+        }
+    }
+
+    /// binds var<uniform> of 4 u32 values at @group(group) @binding(bind)
+    /// on every device : offset of its part , len of its part , size of
+    /// the whole range in the divided dimension and 0 for padding
+    pub fn set_range_binding(&mut self , group : u32 , bind : u32) {
+        self.range_binding = Some((group , bind));
+    }
+
+    /// divides size between the devices , submits the parts to all of
+    /// them and blocks until every result is copied back . workgroups of
+    /// each part are computed like Kernel::workgroups_for_size does .
+    /// bindings of Buffers can not be used because they live on one device .
+    /// when one device fails , parts of the other devices are still waited
+    /// for and copied back , then the first error is returned
+    pub fn compute<'a>(&mut self , size : [u32; 2] , bindings : Vec<MultiBinding<'a>>) -> Result<() , GPUComputingError> {
+        if self.devices.is_empty() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : no device is added to MultiDevice"));
+        }
+
+        if size.contains(&0) {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : range size {:?} is empty" , size)));
+        }
+
+        let weights = self.weights();
+        if weights.iter().all(|weight| *weight == 0.0) {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : every device of MultiDevice has zero weight"));
+        }
+
+        // 2D ranges are divided by rows
+        let divided = if size[1] > 1 { 1 } else { 0 };
+        let items = size[divided];
+        let parts = partition(items , &weights);
+
+        let mut part_bindings : Vec<Vec<Binding<'a>>> = parts.iter()
+            .map(|_| Vec::new())
+            .collect();
+
+        for binding in bindings {
+            match binding {
+                MultiBinding::Whole(binding) => {
+                    for bindings in &mut part_bindings {
+                        let shared = binding.share()
+                            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : group {} binding {} : only read only bindings can be given whole to every device , outputs must be split" , binding.group , binding.bind)))?;
+                        bindings.push(shared);
+                    }
+                },
+                MultiBinding::Split(binding) => {
+                    for (bindings , part) in part_bindings.iter_mut().zip(split(binding , items , &parts)?) {
+                        bindings.push(part);
+                    }
+                },
+            }
+        }
+
+        let range_data : Vec<[u32; 4]> = parts.iter()
+            .map(|part| [part.start , part.len() as u32 , items , 0])
+            .collect();
+
+        let mut jobs : Vec<(usize , Instant , Job)> = Vec::new();
+        let mut first_error : Option<GPUComputingError> = None;
+
+        for (index , (part , mut bindings)) in parts.iter().zip(part_bindings).enumerate() {
+            if part.is_empty() {
+                continue;
+            }
+
+            if let Some((group , bind)) = self.range_binding {
+                bindings.push(Binding::uniform(group , bind , &range_data[index..index + 1]));
+            }
+
+            let mut part_size = [size[0] , size[1] , 1];
+            part_size[divided] = part.len() as u32;
+
+            // parts which are already submitted must still be
+            // waited for , so submitting stops at the first error
+            let device = &self.devices[index];
+            let job = device.kernel.workgroups_for_size(part_size)
+                .and_then(|workgroups| device.context.compute_async(&device.kernel , workgroups , bindings));
+            match job {
+                Ok(job) => jobs.push((index , Instant::now() , job)) ,
+                Err(err) => {
+                    first_error = Some(err);
+                    break;
+                },
+            }
+        }
+
+        // every device works at the same time , they are polled
+        // together so each throughput is measured on its own
+        while !jobs.is_empty() {
+            let mut pending = Vec::with_capacity(jobs.len());
+
+            for (index , start , mut job) in jobs {
+                match job.poll() {
+                    Ok(false) => pending.push((index , start , job)) ,
+                    Ok(true) => {
+                        let seconds = start.elapsed().as_secs_f64().max(f64::EPSILON);
+                        self.devices[index].throughput = Some(parts[index].len() as f64 / seconds);
+                    },
+                    Err(err) => {
+                        first_error.get_or_insert(err);
+                    },
+                }
+            }
+
+            jobs = pending;
+            if !jobs.is_empty() {
+                std::thread::sleep(Duration::from_micros(200));
+            }
+        }
+
+        match first_error {
+            Some(err) => Err(err) ,
+            None => Ok(()) ,
+        }
+    }
+}
+
+fn check_weight(weight : f64) -> Result<() , GPUComputingError> {
+    if weight.is_finite() && weight >= 0.0 {
+        Ok(())
+    } else {
+        Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : weight of device must be zero or positive number but it is {}" , weight)))
+    }
+}
+
+// divides items proportionally to weights , boundaries are rounded
+// from the running sum of weights so every item belongs to one part
+fn partition(items : u32 , weights : &[f64]) -> Vec<Range<u32>> {
+    let total : f64 = weights.iter().sum();
+
+    let mut parts = Vec::with_capacity(weights.len());
+    let mut sum = 0.0;
+    let mut start = 0;
+
+    for (index , weight) in weights.iter().enumerate() {
+        sum += weight;
+        let end = if index + 1 == weights.len() {
+            items
+        } else {
+            ((items as f64 * sum / total).round() as u32).clamp(start , items)
+        };
+        parts.push(start..end);
+        start = end;
+    }
+
+    parts
+}
+
+// divides host data of binding into the parts , every item of
+// the divided dimension has the same number of bytes
+fn split<'a>(binding : Binding<'a> , items : u32 , parts : &[Range<u32>]) -> Result<Vec<Binding<'a>> , GPUComputingError> {
+    let Binding { group , bind , resource } = binding;
+
+    let BindingResource::Host { data , access } = resource else {
+        return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : group {} binding {} : only host data of storage bindings can be split" , group , bind)));
+    };
+
+    let len = data.as_slice().len();
+    if len % items as usize != 0 {
+        return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : group {} binding {} : {} bytes of data can not be divided into {} items of the range" , group , bind , len , items)));
+    }
+    let item_size = len / items as usize;
+
+    let part_binding = |data : HostData<'a>| Binding {
+        group ,
+        bind ,
+        resource : BindingResource::Host {
+            data ,
+            access ,
+        } ,
+    };
+
+    let mut split_parts = Vec::with_capacity(parts.len());
+
+    match data {
+        HostData::Shared(mut rest) => {
+            for part in parts {
+                let (head , tail) = rest.split_at(part.len() * item_size);
+                split_parts.push(part_binding(HostData::Shared(head)));
+                rest = tail;
+            }
+        },
+        HostData::Exclusive(mut rest) => {
+            for part in parts {
+                let (head , tail) = rest.split_at_mut(part.len() * item_size);
+                split_parts.push(part_binding(HostData::Exclusive(head)));
+                rest = tail;
+            }
+        },
+    }
+
+    Ok(split_parts)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // parts must follow each other and cover every item
+    fn assert_covers(parts : &[Range<u32>] , items : u32) {
+        let mut start = 0;
+        for part in parts {
+            assert_eq!(part.start , start , "{:?}" , parts);
+            assert!(part.end >= part.start , "{:?}" , parts);
+            start = part.end;
+        }
+        assert_eq!(start , items , "{:?}" , parts);
+    }
+
+    fn host_slices(parts : &[Binding<'_>]) -> Vec<Vec<u8>> {
+        parts.iter()
+            .map(|part| match &part.resource {
+                BindingResource::Host { data , .. } => data.as_slice().to_vec() ,
+                _ => panic!("split returned other resource than host data") ,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn partition_follows_weights() {
+        let parts = partition(100 , &[1.0 , 3.0]);
+        assert_eq!(parts , vec![0..25 , 25..100]);
+    }
+
+    #[test]
+    fn partition_rounds_boundaries_and_gives_remainder_to_last() {
+        let parts = partition(10 , &[1.0 , 1.0 , 1.0]);
+        assert_eq!(parts , vec![0..3 , 3..7 , 7..10]);
+        assert_covers(&parts , 10);
+
+        let parts = partition(7 , &[0.3 , 0.3 , 0.3]);
+        assert_covers(&parts , 7);
+    }
+
+    #[test]
+    fn partition_gives_nothing_to_zero_weights() {
+        assert_eq!(partition(8 , &[0.0 , 1.0]) , vec![0..0 , 0..8]);
+        assert_eq!(partition(8 , &[1.0 , 0.0 , 1.0]) , vec![0..4 , 4..4 , 4..8]);
+        assert_eq!(partition(8 , &[1.0 , 0.0]) , vec![0..8 , 8..8]);
+    }
+
+    #[test]
+    fn partition_with_fewer_items_than_devices() {
+        let parts = partition(2 , &[1.0 , 1.0 , 1.0 , 1.0]);
+        assert_covers(&parts , 2);
+        assert_eq!(parts.iter().filter(|part| !part.is_empty()).count() , 2);
+
+        let parts = partition(1 , &[1.0 , 1.0 , 1.0]);
+        assert_covers(&parts , 1);
+    }
+
+    #[test]
+    fn split_divides_unaligned_items() {
+        // items of 3 bytes , so boundaries are not multiples of 4
+        let data : Vec<u8> = (0..9).collect();
+        let parts = split(Binding::input(0 , 1 , &data) , 3 , &[0..1 , 1..3]).unwrap();
+
+        assert_eq!(host_slices(&parts) , vec![vec![0 , 1 , 2] , vec![3 , 4 , 5 , 6 , 7 , 8]]);
+        assert!(parts.iter().all(|part| part.group == 0 && part.bind == 1));
+    }
+
+    #[test]
+    fn split_parts_write_to_the_original_data() {
+        let mut data = [0u16; 6];
+        {
+            let parts = split(Binding::output(0 , 0 , &mut data) , 3 , &[0..0 , 0..2 , 2..3]).unwrap();
+            assert_eq!(host_slices(&parts) , vec![vec![] , vec![0; 8] , vec![0; 4]]);
+
+            for (value , mut part) in parts.into_iter().enumerate() {
+                if let BindingResource::Host { data , .. } = &mut part.resource {
+                    data.as_mut_slice().unwrap().fill(value as u8);
+                }
+            }
+        }
+
+        assert_eq!(bytemuck::cast_slice::<u16 , u8>(&data) , &[1 , 1 , 1 , 1 , 1 , 1 , 1 , 1 , 2 , 2 , 2 , 2]);
+    }
+
+    #[test]
+    fn split_rejects_data_which_items_do_not_divide() {
+        let data = [0u8; 10];
+        let Err(err) = split(Binding::input(0 , 0 , &data) , 3 , &[0..1 , 1..3]) else {
+            panic!("10 bytes were divided into 3 items");
+        };
+        assert_eq!(err.status , GPUComputingStatus::InvalidArgument);
+    }
+
+    #[test]
+    fn split_rejects_other_than_host_data() {
+        let data = [0u32; 4];
+        let Err(err) = split(Binding::uniform(0 , 0 , &data) , 4 , &[0..2 , 2..4]) else {
+            panic!("uniform binding was split");
+        };
+        assert_eq!(err.status , GPUComputingStatus::InvalidArgument);
+    }
+}
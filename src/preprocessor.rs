@@ -0,0 +1,475 @@
+use std::collections::HashMap;
+
+use crate::diagnostic::CompileDiagnostic;
+use crate::error::{GPUComputingError , GPUComputingStatus};
+
+
+#[derive(Clone , Debug , Default)]
+/// Preprocessor generates variants of kernel codes from one source
+/// before they are compiled . lines which start with # are directives :
+///
+/// #define NAME value , #undef NAME , #ifdef NAME , #ifndef NAME ,
+/// #else , #endif and #include "name" which pastes the snippet which
+/// is added with that name .
+///
+/// defined names are replaced with their value in the rest of the
+/// code , only whole identifiers are replaced and values can use
+/// other defined names . directive lines and skipped lines are left
+/// empty and included snippets add their own lines , so
+/// Preprocessed keeps where every output line comes from
+pub struct Preprocessor {
+    snippets : HashMap<String , String> ,
+}
+
+impl Preprocessor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// adds code which #include "name" pastes , snippet with
+    /// the same name is replaced
+    pub fn add_snippet(&mut self , name : &str , code : &str) {
+        self.snippets.insert(name.to_string() , code.to_string());
+    }
+
+    /// preprocesses code , defines are set like #define lines before
+    /// the code and the code itself can change them . problems are
+    /// returned as ShaderCompileError with their diagnostics
+    pub fn process(&self , code : &str , defines : &[(&str , &str)]) -> Result<String , GPUComputingError> {
+        Ok(self.process_with_lines(code , defines)?.code)
+    }
+
+    /// works like process but also returns where every line of the
+    /// output comes from , see Preprocessed::map_error
+    pub fn process_with_lines(&self , code : &str , defines : &[(&str , &str)]) -> Result<Preprocessed , GPUComputingError> {
+        let mut state = State {
+            snippets : &self.snippets ,
+            defines : HashMap::new() ,
+            includes : Vec::new() ,
+            output : String::with_capacity(code.len()) ,
+            lines : Vec::new() ,
+            origins : Vec::new() ,
+            columns : Vec::new() ,
+        };
+
+        for (name , value) in defines {
+            if !is_identifier(name) {
+                return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : define name {:?} is not an identifier" , name)));
+            }
+            state.defines.insert(name.to_string() , value.to_string());
+        }
+
+        state.process_source(None , code)?;
+        Ok(Preprocessed {
+            code : state.output ,
+            lines : state.lines ,
+            origins : state.origins ,
+        })
+    }
+}
+
+#[derive(Clone , Debug , PartialEq , Eq)]
+/// line of the code or of an included snippet
+pub struct SourceLine {
+    /// name of the snippet , None for the code itself
+    pub snippet : Option<String> ,
+    /// line number in the code or snippet , it starts from 1
+    pub line : u32 ,
+}
+
+#[derive(Clone , Debug)]
+/// code which Preprocessor generated and the
+/// source line of each of its lines
+pub struct Preprocessed {
+    pub code : String ,
+    lines : Vec<SourceLine> ,
+    origins : Vec<LineOrigin> ,
+}
+
+#[derive(Clone , Debug)]
+// text of the source line which an output line comes from
+struct LineOrigin {
+    text : String ,
+    // byte offsets in the output line and in text where tokens
+    // start , copied is false for defined names which are replaced
+    columns : Vec<(usize , usize , bool)> ,
+}
+
+impl LineOrigin {
+    // column of the output line to column of text , both start from 1 ,
+    // columns inside of replaced values point to the defined name
+    fn column(&self , column : u32) -> u32 {
+        let Some(offset) = (column as usize).checked_sub(1) else {
+            return 0;
+        };
+
+        let source = match self.columns.iter().rev().find(|(output , _ , _)| *output <= offset) {
+            Some(&(output , source , true)) => source + offset - output ,
+            Some(&(_ , source , false)) => source ,
+            None => offset ,
+        };
+        source.min(self.text.len()) as u32 + 1
+    }
+}
+
+impl Preprocessed {
+    /// source line of line of the generated code , line starts from 1
+    pub fn source_line(&self , line : u32) -> Option<&SourceLine> {
+        self.lines.get((line as usize).checked_sub(1)?)
+    }
+
+    /// moves lines , columns and snippets of the diagnostics of err ,
+    /// which are found in the generated code , to the code or the
+    /// snippets which they come from . diagnostics of snippets name the
+    /// snippet and the message of err is rebuilt from the diagnostics ,
+    /// because the report of naga quotes the generated code
+    pub fn map_error(&self , mut err : GPUComputingError) -> GPUComputingError {
+        let mut locations : Vec<String> = Vec::new();
+
+        for diagnostic in &mut err.diagnostics {
+            let Some(index) = (diagnostic.line as usize).checked_sub(1).filter(|index| *index < self.lines.len()) else {
+                continue;
+            };
+            let (source , origin) = (&self.lines[index] , &self.origins[index]);
+
+            // snippet of the diagnostic can have more than one line
+            let end = (index + diagnostic.snippet.lines().count().max(1)).min(self.origins.len());
+            diagnostic.snippet = self.origins[index..end].iter()
+                .map(|origin| origin.text.as_str())
+                .collect::<Vec<&str>>()
+                .join("\n");
+            diagnostic.column = origin.column(diagnostic.column);
+            diagnostic.line = source.line;
+            if let Some(snippet) = &source.snippet {
+                diagnostic.message = format!("in snippet {:?} , {}" , snippet , diagnostic.message);
+            }
+
+            locations.push(format!("line {} column {} : {}\n{}" , diagnostic.line , diagnostic.column , diagnostic.message , diagnostic.snippet));
+        }
+
+        if !locations.is_empty() {
+            let summary = err.message.lines().next().unwrap_or_default();
+            err.message = format!("{}\n{}" , summary , locations.join("\n"));
+        }
+        err
+    }
+}
+
+fn is_identifier(name : &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+// #ifdef or #ifndef block which is not closed yet
+struct Conditional {
+    line : u32 ,
+    // lines of the enclosing block are kept
+    parent_active : bool ,
+    // condition of #ifdef or #ifndef was true
+    taken : bool ,
+    in_else : bool ,
+}
+
+impl Conditional {
+    fn active(&self) -> bool {
+        self.parent_active && (self.taken != self.in_else)
+    }
+}
+
+struct State<'p> {
+    snippets : &'p HashMap<String , String> ,
+    defines : HashMap<String , String> ,
+    // snippets which are being included , for finding cycles
+    includes : Vec<String> ,
+    output : String ,
+    // source line of every line of output
+    lines : Vec<SourceLine> ,
+    origins : Vec<LineOrigin> ,
+    // token columns of the output line which is being written
+    columns : Vec<(usize , usize , bool)> ,
+}
+
+impl State<'_> {
+    // snippet is name of the included snippet or None for the code itself
+    fn process_source(&mut self , snippet : Option<&str> , code : &str) -> Result<() , GPUComputingError> {
+        let mut conditionals : Vec<Conditional> = Vec::new();
+
+        for (index , line) in code.lines().enumerate() {
+            let line_number = index as u32 + 1;
+            let error = |message : String| error(snippet , line_number , line , message);
+            let active = conditionals.last().is_none_or(Conditional::active);
+
+            let Some(directive) = line.trim_start().strip_prefix('#') else {
+                if active {
+                    self.expand_line(line);
+                }
+                self.end_line(snippet , line_number , line);
+                continue;
+            };
+
+            let directive = directive.split("//").next().unwrap_or_default().trim();
+            let (keyword , rest) = directive.split_once(char::is_whitespace).unwrap_or((directive , ""));
+            let rest = rest.trim();
+
+            match keyword {
+                "ifdef" | "ifndef" => {
+                    if !is_identifier(rest) {
+                        return Err(error(format!("#{} needs one name" , keyword)));
+                    }
+                    conditionals.push(Conditional {
+                        line : line_number ,
+                        parent_active : active ,
+                        taken : self.defines.contains_key(rest) == (keyword == "ifdef") ,
+                        in_else : false ,
+                    });
+                },
+                "else" => match conditionals.last_mut() {
+                    Some(conditional) if !conditional.in_else => conditional.in_else = true ,
+                    Some(_) => return Err(error("#else is used twice in one #ifdef block".to_string())) ,
+                    None => return Err(error("#else without #ifdef or #ifndef".to_string())) ,
+                },
+                "endif" => {
+                    if conditionals.pop().is_none() {
+                        return Err(error("#endif without #ifdef or #ifndef".to_string()));
+                    }
+                },
+                // other directives of skipped blocks are ignored
+                _ if !active => {},
+                "define" => {
+                    let (name , value) = rest.split_once(char::is_whitespace).unwrap_or((rest , ""));
+                    if !is_identifier(name) {
+                        return Err(error(format!("#define needs a name , {:?} is not an identifier" , name)));
+                    }
+                    self.defines.insert(name.to_string() , value.trim().to_string());
+                },
+                "undef" => {
+                    if !is_identifier(rest) {
+                        return Err(error("#undef needs one name".to_string()));
+                    }
+                    self.defines.remove(rest);
+                },
+                "include" => {
+                    let name = rest.strip_prefix('"').and_then(|rest| rest.strip_suffix('"'))
+                        .or_else(|| rest.strip_prefix('<').and_then(|rest| rest.strip_suffix('>')))
+                        .ok_or_else(|| error("#include needs snippet name in quotes".to_string()))?;
+                    let included = self.snippets.get(name)
+                        .ok_or_else(|| error(format!("snippet {:?} is not registered" , name)))?;
+                    if self.includes.iter().any(|include| include == name) {
+                        return Err(error(format!("snippet {:?} includes itself" , name)));
+                    }
+
+                    // lines of the snippet replace the #include line
+                    self.includes.push(name.to_string());
+                    self.process_source(Some(name) , included)?;
+                    self.includes.pop();
+                    continue;
+                },
+                _ => return Err(error(format!("unknown directive #{}" , keyword))) ,
+            }
+
+            self.end_line(snippet , line_number , line);
+        }
+
+        if let Some(conditional) = conditionals.last() {
+            let line = code.lines().nth(conditional.line as usize - 1).unwrap_or_default();
+            return Err(error(snippet , conditional.line , line , "#ifdef or #ifndef is not closed with #endif".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn end_line(&mut self , snippet : Option<&str> , line_number : u32 , line : &str) {
+        self.output.push('\n');
+        self.lines.push(SourceLine {
+            snippet : snippet.map(str::to_string) ,
+            line : line_number ,
+        });
+        self.origins.push(LineOrigin {
+            text : line.to_string() ,
+            columns : std::mem::take(&mut self.columns) ,
+        });
+    }
+
+    // expands line token by token , so it is known where
+    // each token of the output line starts in line
+    fn expand_line(&mut self , line : &str) {
+        let start = self.output.len();
+        let mut rest = line;
+
+        while !rest.is_empty() {
+            let (token , tail) = rest.split_at(token_len(rest));
+            let copied = !self.defines.contains_key(token);
+
+            // copied tokens which follow each other keep one entry
+            if !copied || self.columns.last().is_none_or(|(_ , _ , last)| !last) {
+                self.columns.push((self.output.len() - start , line.len() - rest.len() , copied));
+            }
+
+            self.expand(token , &mut Vec::new());
+            rest = tail;
+        }
+    }
+
+    // writes text to output with defined names replaced , expanding
+    // holds the names which are being replaced , so a value which
+    // uses its own name does not loop forever
+    fn expand(&mut self , text : &str , expanding : &mut Vec<String>) {
+        let mut rest = text;
+
+        while !rest.is_empty() {
+            let (token , tail) = rest.split_at(token_len(rest));
+            rest = tail;
+
+            let value = match self.defines.get(token) {
+                Some(value) if !expanding.iter().any(|name| name == token) => value.clone() ,
+                _ => {
+                    self.output.push_str(token);
+                    continue;
+                },
+            };
+
+            expanding.push(token.to_string());
+            self.expand(&value , expanding);
+            expanding.pop();
+        }
+    }
+}
+
+// numbers like 1u or 0x1f are one token , so
+// their suffixes are never replaced
+fn token_len(text : &str) -> usize {
+    match text.chars().next() {
+        Some(c) if c.is_alphanumeric() || c == '_' => text.find(|c : char| !(c.is_alphanumeric() || c == '_')).unwrap_or(text.len()) ,
+        Some(c) => c.len_utf8() ,
+        None => 0 ,
+    }
+}
+
+fn error(snippet : Option<&str> , line_number : u32 , line : &str , message : String) -> GPUComputingError {
+    let message = match snippet {
+        Some(snippet) => format!("in snippet {:?} , {}" , snippet , message) ,
+        None => message ,
+    };
+
+    let diagnostic = CompileDiagnostic {
+        message : message.clone() ,
+        line : line_number ,
+        column : 1 ,
+        snippet : line.to_string() ,
+    };
+
+    GPUComputingError::new(GPUComputingStatus::ShaderCompileError , format!("ERROR : could not preprocess the kernel code , line {} : {}" , line_number , message))
+        .with_diagnostics(vec![diagnostic])
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defines_replace_whole_identifiers() {
+        let preprocessor = Preprocessor::new();
+        let code = preprocessor.process("#define SIZE 4u\n#define TOTAL SIZE * SIZE\nlet a = TOTAL + SIZE_X + 0x1SIZE;" , &[("SIZE_X" , "2")]).unwrap();
+
+        assert_eq!(code , "\n\nlet a = 4u * 4u + 2 + 0x1SIZE;\n");
+    }
+
+    #[test]
+    fn self_referencing_define_stops() {
+        let preprocessor = Preprocessor::new();
+        let code = preprocessor.process("#define A A + 1\nA" , &[]).unwrap();
+
+        assert_eq!(code , "\nA + 1\n");
+    }
+
+    #[test]
+    fn nested_ifdef_and_else() {
+        let preprocessor = Preprocessor::new();
+        let code = "#ifdef A\n#ifndef B\na_only\n#else\na_and_b\n#endif\n#else\nno_a\n#endif";
+
+        let lines = |defines : &[(&str , &str)]| -> Vec<String> {
+            preprocessor.process(code , defines).unwrap()
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+
+        assert_eq!(lines(&[]) , ["no_a"]);
+        assert_eq!(lines(&[("A" , "")]) , ["a_only"]);
+        assert_eq!(lines(&[("A" , "") , ("B" , "")]) , ["a_and_b"]);
+    }
+
+    #[test]
+    fn unclosed_ifdef_is_reported() {
+        let preprocessor = Preprocessor::new();
+        let err = preprocessor.process("x\n#ifdef A\ny" , &[]).unwrap_err();
+
+        assert_eq!(err.status , GPUComputingStatus::ShaderCompileError);
+        assert_eq!(err.diagnostics[0].line , 2);
+    }
+
+    #[test]
+    fn include_cycles_are_reported() {
+        let mut preprocessor = Preprocessor::new();
+        preprocessor.add_snippet("a" , "#include \"b\"");
+        preprocessor.add_snippet("b" , "x\n#include \"a\"");
+
+        let err = preprocessor.process("#include \"a\"" , &[]).unwrap_err();
+
+        assert_eq!(err.status , GPUComputingStatus::ShaderCompileError);
+        assert!(err.message.contains("includes itself") , "{}" , err.message);
+        assert_eq!(err.diagnostics[0].line , 2);
+        assert!(err.diagnostics[0].message.starts_with("in snippet \"b\"") , "{}" , err.diagnostics[0].message);
+    }
+
+    #[test]
+    fn lines_map_back_through_includes() {
+        let mut preprocessor = Preprocessor::new();
+        preprocessor.add_snippet("three" , "s1\ns2\ns3");
+
+        let preprocessed = preprocessor.process_with_lines("a\n#include \"three\"\nb" , &[]).unwrap();
+        assert_eq!(preprocessed.code , "a\ns1\ns2\ns3\nb\n");
+
+        let source = |line : u32| preprocessed.source_line(line).cloned();
+        assert_eq!(source(1) , Some(SourceLine { snippet : None , line : 1 }));
+        assert_eq!(source(3) , Some(SourceLine { snippet : Some("three".to_string()) , line : 2 }));
+        assert_eq!(source(5) , Some(SourceLine { snippet : None , line : 3 }));
+        assert_eq!(source(6) , None);
+
+        let err = GPUComputingError::new(GPUComputingStatus::ShaderCompileError , "ERROR")
+            .with_diagnostics(vec![
+                CompileDiagnostic { message : "bad".to_string() , line : 5 , column : 1 , snippet : "b".to_string() } ,
+                CompileDiagnostic { message : "worse".to_string() , line : 4 , column : 1 , snippet : "s3".to_string() } ,
+            ]);
+        let err = preprocessed.map_error(err);
+
+        assert_eq!(err.diagnostics[0].line , 3);
+        assert_eq!(err.diagnostics[0].message , "bad");
+        assert_eq!(err.diagnostics[1].line , 3);
+        assert_eq!(err.diagnostics[1].message , "in snippet \"three\" , worse");
+    }
+
+    #[test]
+    fn naga_error_after_include_points_to_source() {
+        let mut preprocessor = Preprocessor::new();
+        preprocessor.add_snippet("consts" , "const A : u32 = 1u;\nconst B : u32 = 2u;\nconst C : u32 = 3u;");
+
+        let line = "fn f() -> u32 { return VALUE + A }";
+        let code = format!("#define VALUE 70000u\n#include \"consts\"\n{}" , line);
+        let preprocessed = preprocessor.process_with_lines(&code , &[]).unwrap();
+
+        let parse_error = naga::front::wgsl::parse_str(&preprocessed.code).unwrap_err();
+        let err = preprocessed.map_error(crate::diagnostic::wgsl_error(&parse_error , &preprocessed.code));
+
+        let diagnostic = &err.diagnostics[0];
+        assert_eq!(diagnostic.line , 3);
+        assert_eq!(diagnostic.column as usize , line.find('}').unwrap() + 1);
+        assert_eq!(diagnostic.snippet , line);
+        assert!(err.message.contains(&format!("line 3 column {} : " , diagnostic.column)) , "{}" , err.message);
+        assert!(err.message.contains(line) , "{}" , err.message);
+        assert!(!err.message.contains("70000u") , "{}" , err.message);
+    }
+}
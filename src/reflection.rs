@@ -0,0 +1,611 @@
+use crate::{from_ptr , EMKernel , GPUBindingKind , GPUDataAccess , GPU_KERNELS};
+use crate::binding::{Binding , BindingResource};
+use crate::diagnostic;
+use crate::error::{ffi_call , GPUComputingError , GPUComputingStatus};
+use crate::texture::{GPUTextureFormat , GPUTextureDimension};
+
+
+#[repr(C)]
+#[derive(Debug , Clone , Copy , PartialEq , Eq)]
+/// since v7.0.0 scalar type of elements of reflected bindings
+pub enum GPUScalarKind {
+    /// structs , samplers and types which have no single scalar type
+    NoScalar = 0 ,
+    Sint = 1 ,
+    Uint = 2 ,
+    Float = 3 ,
+    Bool = 4 ,
+}
+
+#[repr(C)]
+#[derive(Debug , Clone , Copy , PartialEq , Eq)]
+/// since v7.0.0 one @group @binding variable which entry point
+/// of the kernel code uses
+pub struct GPUBindingInfo {
+    /// @group index of the variable
+    pub group : u32 ,
+    /// @binding index of the variable
+    pub bind : u32 ,
+    /// address space of the variable , which is the kind
+    /// field that DataBinder of it needs
+    pub kind : GPUBindingKind ,
+    /// access mode of the variable , var<uniform> , sampled
+    /// textures and samplers are ReadOnly
+    pub access : GPUDataAccess ,
+    /// scalar type of one element , for textures it is
+    /// the type which textureLoad and textureSample return
+    pub element_kind : GPUScalarKind ,
+    /// bytes of one scalar of the element
+    pub element_width : u32 ,
+    /// scalars of one element , 1 for scalars , 2 to 4 for vectors ,
+    /// columns * rows for matrices and 0 for structs
+    pub element_components : u32 ,
+    /// bytes between elements of arrays , it is 0 when the variable
+    /// is not an array . structs which end with runtime sized array
+    /// are reflected as that array
+    pub stride : u32 ,
+    /// number of elements of fixed size arrays , 0 for
+    /// runtime sized arrays and variables which are not arrays
+    pub array_len : u32 ,
+    /// minimum number of bytes which bound data must have , runtime
+    /// sized arrays are counted with one element . 0 for textures and samplers
+    pub min_size : u64 ,
+    /// format of storage textures , kernel codes which declare
+    /// formats that GPUTextureFormat does not have can not be registered
+    pub texture_format : GPUTextureFormat ,
+    /// dimension of textures
+    pub texture_dimension : GPUTextureDimension ,
+}
+
+#[derive(Debug , Clone , PartialEq , Eq)]
+/// KernelReflection describes what entry point of a
+/// Kernel needs , see Kernel::reflection
+pub struct KernelReflection {
+    /// every @group @binding which the entry point uses ,
+    /// sorted by group and bind
+    pub bindings : Vec<GPUBindingInfo> ,
+    /// @workgroup_size of the entry point
+    pub workgroup_size : [u32; 3] ,
+    /// size of var<push_constant> in bytes , 0 when it is not used
+    pub push_constant_size : u32 ,
+}
+
+impl KernelReflection {
+    /// number of bind groups which the entry point uses
+    pub(crate) fn bind_group_count(&self) -> u32 {
+        self.bindings.iter().map(|binding| binding.group + 1).max().unwrap_or(0)
+    }
+
+    /// checks bindings against the reflected layout , so mismatches are
+    /// reported with the group and binding instead of a validation error
+    /// of the gpu side . every binding which the entry point uses must be
+    /// provided once with matching kind , format and enough data
+    pub(crate) fn check_bindings(&self , bindings : &[Binding<'_>]) -> Result<() , GPUComputingError> {
+        let mut provided = vec![false; self.bindings.len()];
+
+        for binding in bindings {
+            let index = self.bindings.binary_search_by_key(&(binding.group , binding.bind) , |info| (info.group , info.bind))
+                .map_err(|_| GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : group {} binding {} is not used by entry point of the kernel code" , binding.group , binding.bind)))?;
+
+            if std::mem::replace(&mut provided[index] , true) {
+                return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : group {} binding {} is provided more than once" , binding.group , binding.bind)));
+            }
+
+            self.bindings[index].check(binding)?;
+        }
+
+        if let Some(info) = self.bindings.iter().zip(&provided).find(|(_ , provided)| !**provided).map(|(info , _)| info) {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : group {} binding {} ({:?}) of the kernel code is not provided" , info.group , info.bind , info.kind)));
+        }
+
+        Ok(())
+    }
+}
+
+impl GPUBindingInfo {
+    fn check(&self , binding : &Binding<'_>) -> Result<() , GPUComputingError> {
+        let error = |message : String| Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : group {} binding {} : {}" , self.group , self.bind , message)));
+
+        // kind of the binding and its size in bytes
+        let (kind , size) = match &binding.resource {
+            BindingResource::Buffer { raw , .. } => {
+                // Buffers can be bound to var<storage> and var<uniform>
+                let kind = if self.kind == GPUBindingKind::Uniform {
+                    GPUBindingKind::Uniform
+                } else {
+                    GPUBindingKind::Storage
+                };
+                (kind , Some(raw.size()))
+            },
+            BindingResource::Host { data , .. } => (GPUBindingKind::Storage , Some(data.as_slice().len() as u64)) ,
+            // uniform data is padded to 16 bytes
+            BindingResource::Uniform { data } => (GPUBindingKind::Uniform , Some((data.len() as u64).next_multiple_of(16))) ,
+            BindingResource::Texture { sampled : true , .. } => (GPUBindingKind::SampledTexture , None) ,
+            BindingResource::Texture { sampled : false , .. } => (GPUBindingKind::StorageTexture , None) ,
+            BindingResource::Sampler { .. } => (GPUBindingKind::Sampler , None) ,
+        };
+
+        if kind != self.kind {
+            return error(format!("kernel code declares {:?} but {:?} is provided" , self.kind , kind));
+        }
+
+        if let Some(size) = size {
+            if size < self.min_size {
+                return error(format!("kernel code needs at least {} bytes but {} bytes are provided" , self.min_size , size));
+            }
+        }
+
+        if let BindingResource::Texture { info , .. } = &binding.resource {
+            if info.dimension != self.texture_dimension {
+                return error(format!("kernel code declares {:?} texture but {:?} is provided" , self.texture_dimension , info.dimension));
+            }
+
+            if kind == GPUBindingKind::StorageTexture && info.format != self.texture_format {
+                return error(format!("kernel code declares {:?} storage texture but {:?} is provided" , self.texture_format , info.format));
+            }
+
+            let element_kind = texel_kind(info.format);
+            if kind == GPUBindingKind::SampledTexture && element_kind != self.element_kind {
+                return error(format!("kernel code samples {:?} texels but format {:?} has {:?} texels" , self.element_kind , info.format , element_kind));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+fn scalar_kind(kind : naga::ScalarKind) -> GPUScalarKind {
+    match kind {
+        naga::ScalarKind::Sint => GPUScalarKind::Sint ,
+        naga::ScalarKind::Uint => GPUScalarKind::Uint ,
+        naga::ScalarKind::Float => GPUScalarKind::Float ,
+        naga::ScalarKind::Bool => GPUScalarKind::Bool ,
+        _ => GPUScalarKind::NoScalar ,
+    }
+}
+
+// kind , width and number of components of an element type
+fn element_info(inner : &naga::TypeInner) -> (GPUScalarKind , u32 , u32) {
+    match *inner {
+        naga::TypeInner::Scalar(scalar) | naga::TypeInner::Atomic(scalar) => {
+            (scalar_kind(scalar.kind) , scalar.width as u32 , 1)
+        },
+        naga::TypeInner::Vector { size , scalar } => {
+            (scalar_kind(scalar.kind) , scalar.width as u32 , size as u32)
+        },
+        naga::TypeInner::Matrix { columns , rows , scalar } => {
+            (scalar_kind(scalar.kind) , scalar.width as u32 , columns as u32 * rows as u32)
+        },
+        _ => (GPUScalarKind::NoScalar , 0 , 0) ,
+    }
+}
+
+// storage formats which wgsl accepts without extra features are
+// all covered by GPUTextureFormat , other formats can not be bound
+fn texture_format(format : naga::StorageFormat) -> Result<GPUTextureFormat , GPUComputingError> {
+    Ok(match format {
+        naga::StorageFormat::Rgba8Unorm => GPUTextureFormat::Rgba8Unorm ,
+        naga::StorageFormat::Rgba8Snorm => GPUTextureFormat::Rgba8Snorm ,
+        naga::StorageFormat::Rgba8Uint => GPUTextureFormat::Rgba8Uint ,
+        naga::StorageFormat::Rgba8Sint => GPUTextureFormat::Rgba8Sint ,
+        naga::StorageFormat::Rgba16Uint => GPUTextureFormat::Rgba16Uint ,
+        naga::StorageFormat::Rgba16Sint => GPUTextureFormat::Rgba16Sint ,
+        naga::StorageFormat::Rgba16Float => GPUTextureFormat::Rgba16Float ,
+        naga::StorageFormat::R32Uint => GPUTextureFormat::R32Uint ,
+        naga::StorageFormat::R32Sint => GPUTextureFormat::R32Sint ,
+        naga::StorageFormat::R32Float => GPUTextureFormat::R32Float ,
+        naga::StorageFormat::Rg32Uint => GPUTextureFormat::Rg32Uint ,
+        naga::StorageFormat::Rg32Sint => GPUTextureFormat::Rg32Sint ,
+        naga::StorageFormat::Rg32Float => GPUTextureFormat::Rg32Float ,
+        naga::StorageFormat::Rgba32Uint => GPUTextureFormat::Rgba32Uint ,
+        naga::StorageFormat::Rgba32Sint => GPUTextureFormat::Rgba32Sint ,
+        naga::StorageFormat::Rgba32Float => GPUTextureFormat::Rgba32Float ,
+        naga::StorageFormat::R8Unorm => GPUTextureFormat::R8Unorm ,
+        format => return Err(GPUComputingError::new(GPUComputingStatus::UnsupportedFeature , format!("ERROR : kernel code declares storage texture with format {:?} which GPUTextureFormat does not support" , format))) ,
+    })
+}
+
+// type which textureLoad returns for the format
+fn texel_kind(format : GPUTextureFormat) -> GPUScalarKind {
+    match format.to_wgpu().sample_type(None , None) {
+        Some(wgpu::TextureSampleType::Sint) => GPUScalarKind::Sint ,
+        Some(wgpu::TextureSampleType::Uint) => GPUScalarKind::Uint ,
+        _ => GPUScalarKind::Float ,
+    }
+}
+
+fn binding_info(module : &naga::Module , var : &naga::GlobalVariable , binding : &naga::ResourceBinding) -> Result<GPUBindingInfo , GPUComputingError> {
+    let inner = &module.types[var.ty].inner;
+
+    let mut info = GPUBindingInfo {
+        group : binding.group ,
+        bind : binding.binding ,
+        kind : GPUBindingKind::Storage ,
+        access : GPUDataAccess::ReadOnly ,
+        element_kind : GPUScalarKind::NoScalar ,
+        element_width : 0 ,
+        element_components : 0 ,
+        stride : 0 ,
+        array_len : 0 ,
+        min_size : 0 ,
+        texture_format : GPUTextureFormat::default() ,
+        texture_dimension : GPUTextureDimension::default() ,
+    };
+
+    match *inner {
+        naga::TypeInner::Image { dim , class , .. } => {
+            info.texture_dimension = match dim {
+                naga::ImageDimension::D3 => GPUTextureDimension::Texture3D ,
+                _ => GPUTextureDimension::Texture2D ,
+            };
+            info.element_width = 4;
+            info.element_components = 4;
+
+            match class {
+                naga::ImageClass::Sampled { kind , .. } => {
+                    info.kind = GPUBindingKind::SampledTexture;
+                    info.element_kind = scalar_kind(kind);
+                },
+                naga::ImageClass::Depth { .. } => {
+                    info.kind = GPUBindingKind::SampledTexture;
+                    info.element_kind = GPUScalarKind::Float;
+                    info.element_components = 1;
+                },
+                naga::ImageClass::Storage { format , access } => {
+                    info.kind = GPUBindingKind::StorageTexture;
+                    info.access = match (access.contains(naga::StorageAccess::LOAD) , access.contains(naga::StorageAccess::STORE)) {
+                        (true , true) => GPUDataAccess::ReadWrite ,
+                        (false , true) => GPUDataAccess::WriteOnly ,
+                        _ => GPUDataAccess::ReadOnly ,
+                    };
+                    info.texture_format = texture_format(format)?;
+                    info.element_kind = texel_kind(info.texture_format);
+                },
+            }
+        },
+        naga::TypeInner::Sampler { .. } => {
+            info.kind = GPUBindingKind::Sampler;
+        },
+        _ => {
+            match var.space {
+                naga::AddressSpace::Uniform => {
+                    info.kind = GPUBindingKind::Uniform;
+                },
+                naga::AddressSpace::Storage { access } if access.contains(naga::StorageAccess::STORE) => {
+                    info.access = GPUDataAccess::ReadWrite;
+                },
+                _ => {},
+            }
+
+            info.min_size = inner.size(module.to_ctx()) as u64;
+
+            // runtime sized array at the end of a struct
+            // is what the bound data mostly contains
+            let array = match inner {
+                naga::TypeInner::Struct { members , .. } => members.last()
+                    .map(|member| &module.types[member.ty].inner)
+                    .filter(|member| matches!(member , naga::TypeInner::Array { size : naga::ArraySize::Dynamic , .. })) ,
+                naga::TypeInner::Array { .. } => Some(inner) ,
+                _ => None ,
+            };
+
+            let element = match array {
+                Some(naga::TypeInner::Array { base , size , stride }) => {
+                    info.stride = *stride;
+                    if let naga::ArraySize::Constant(len) = size {
+                        info.array_len = len.get();
+                    }
+                    &module.types[*base].inner
+                },
+                _ => inner ,
+            };
+
+            (info.element_kind , info.element_width , info.element_components) = element_info(element);
+        },
+    }
+
+    Ok(info)
+}
+
+/// reflects compute entry_point of the module which passed validation
+/// of naga , it fails when the module has no such entry point or one of
+/// its bindings can not be described by GPUBindingInfo
+pub(crate) fn reflect_module(module : &naga::Module , info : &naga::valid::ModuleInfo , entry_point : &str) -> Result<KernelReflection , GPUComputingError> {
+    let ep_index = module.entry_points.iter()
+        .position(|ep| ep.name == entry_point && ep.stage == naga::ShaderStage::Compute)
+        .ok_or_else(|| diagnostic::entry_point_error(entry_point))?;
+    let ep_info = info.get_entry_point(ep_index);
+
+    let mut bindings : Vec<GPUBindingInfo> = Vec::new();
+    let mut push_constant_size = 0;
+
+    for (handle , var) in module.global_variables.iter() {
+        if ep_info[handle].is_empty() {
+            continue;
+        }
+
+        if let Some(binding) = &var.binding {
+            bindings.push(binding_info(module , var , binding)?);
+        }
+
+        if var.space == naga::AddressSpace::PushConstant {
+            push_constant_size = module.types[var.ty].inner.size(module.to_ctx())
+                .next_multiple_of(wgpu::PUSH_CONSTANT_ALIGNMENT);
+        }
+    }
+
+    bindings.sort_by_key(|binding| (binding.group , binding.bind));
+
+    Ok(KernelReflection {
+        bindings ,
+        workgroup_size : module.entry_points[ep_index].workgroup_size ,
+        push_constant_size ,
+    })
+}
+
+
+#[repr(C)]
+#[derive(Debug)]
+/// since v7.0.0 what emcompute_kernel_reflect writes , it must
+/// be freed with emcompute_free_kernel_reflection after usage
+pub struct GPUKernelReflection {
+    /// pointer to array of every @group @binding which entry
+    /// point uses , sorted by group and bind
+    pub bindings : *mut GPUBindingInfo ,
+    /// len of bindings array
+    pub bindings_len : usize ,
+    /// @workgroup_size of the entry point in x dimension
+    pub workgroup_size_x : u32 ,
+    /// @workgroup_size of the entry point in y dimension
+    pub workgroup_size_y : u32 ,
+    /// @workgroup_size of the entry point in z dimension
+    pub workgroup_size_z : u32 ,
+    /// size of var<push_constant> in bytes , 0 when it is not used
+    pub push_constant_size : u32 ,
+}
+
+#[no_mangle]
+// SAFETY : reflection must point to writable GPUKernelReflection , it is only written and never read
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 writes what the entry point of registered kernel code
+/// needs to reflection : its bindings with their address space , access
+/// mode , element type and stride , its workgroup size and push constant size .
+/// so DataBinders can be checked or generated by your program
+pub extern "C" fn emcompute_kernel_reflect(kernel_code : *mut EMKernel , reflection : *mut GPUKernelReflection) -> GPUComputingStatus {
+    ffi_call(|| {
+        if reflection.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL reflection provided for emcompute_kernel_reflect"));
+        }
+
+        let kernel = GPU_KERNELS.lock().unwrap()
+            .get(from_ptr(kernel_code))
+            .map(|kernel| kernel.kernel.clone())
+            .ok_or_else(|| GPUComputingError::new(GPUComputingStatus::InvalidHandle , "ERROR : invalid or destroyed EMKernel provided for emcompute_kernel_reflect"))?;
+
+        let real_reflection = kernel.reflection();
+
+        // len and capacity must be equal for emcompute_free_kernel_reflection
+        let bindings = Box::into_raw(real_reflection.bindings.clone().into_boxed_slice());
+        let [workgroup_size_x , workgroup_size_y , workgroup_size_z] = real_reflection.workgroup_size;
+
+        // reflection arg may point to uninitialized memory
+        // on C side , so it must not be dropped
+        unsafe {
+            std::ptr::write(reflection , GPUKernelReflection {
+                bindings : bindings as *mut GPUBindingInfo ,
+                bindings_len : bindings.len() ,
+                workgroup_size_x ,
+                workgroup_size_y ,
+                workgroup_size_z ,
+                push_constant_size : real_reflection.push_constant_size ,
+            });
+        }
+
+        Ok(())
+    })
+}
+
+#[no_mangle]
+// SAFETY : reflection must point to GPUKernelReflection which emcompute_kernel_reflect wrote and which is not freed yet
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+/// since v7.0.0 frees memory of GPUKernelReflection
+/// which emcompute_kernel_reflect wrote
+pub extern "C" fn emcompute_free_kernel_reflection(reflection : *mut GPUKernelReflection) -> GPUComputingStatus {
+    ffi_call(|| {
+        if reflection.is_null() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , "ERROR : NULL reflection provided for emcompute_free_kernel_reflection"));
+        }
+
+        unsafe {
+            let reflection = &mut *reflection;
+            if !reflection.bindings.is_null() {
+                drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(reflection.bindings , reflection.bindings_len)));
+            }
+            reflection.bindings = std::ptr::null_mut();
+            reflection.bindings_len = 0;
+        }
+
+        Ok(())
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::texture::GPUTextureInfo;
+
+    const CODE : &str = "
+        @group(0) @binding(0) var<storage, read_write> a : array<u32>;
+        @group(0) @binding(1) var<storage, read> b : array<vec2<i32>, 16>;
+        @group(1) @binding(0) var<uniform> p : vec4<f32>;
+        @group(1) @binding(1) var t : texture_2d<f32>;
+        @group(1) @binding(2) var o : texture_storage_2d<r32uint, write>;
+        @group(2) @binding(0) var<storage, read> unused : array<f32>;
+        @compute @workgroup_size(8 , 4 , 1)
+        fn main(@builtin(global_invocation_id) id : vec3<u32>) {
+            a[id.x] = u32(b[0].x) + u32(p.x) + u32(textureLoad(t , vec2<i32>(0) , 0).x);
+            textureStore(o , vec2<i32>(0) , vec4<u32>(1u));
+        }";
+
+    fn reflect(code : &str) -> Result<KernelReflection , GPUComputingError> {
+        let module = naga::front::wgsl::parse_str(code).unwrap();
+        let info = naga::valid::Validator::new(naga::valid::ValidationFlags::all() , naga::valid::Capabilities::all())
+            .validate(&module)
+            .unwrap();
+        reflect_module(&module , &info , "main")
+    }
+
+    fn texture_info(format : GPUTextureFormat , dimension : GPUTextureDimension) -> GPUTextureInfo {
+        GPUTextureInfo {
+            format ,
+            dimension ,
+            width : 1 ,
+            height : 1 ,
+            depth : 1 ,
+        }
+    }
+
+    // message of the error which check_bindings returns for the
+    // valid bindings of CODE after change is applied to them
+    fn check_error<F>(change : F) -> String
+    where F : for<'a> FnOnce(&mut Vec<Binding<'a>> , &'a mut [u32] , &'a mut [u32]) {
+        let reflection = reflect(CODE).unwrap();
+        let (mut a , b , p , t) = ([0u32; 4] , [0i32; 32] , [0f32; 4] , [0f32; 4]);
+        let (mut o , mut extra) = ([0u32; 1] , [0u32; 64]);
+
+        let mut bindings = vec![
+            Binding::read_write(0 , 0 , &mut a) ,
+            Binding::input(0 , 1 , &b) ,
+            Binding::uniform(1 , 0 , &p) ,
+            Binding::sampled_texture(1 , 1 , texture_info(GPUTextureFormat::Rgba32Float , GPUTextureDimension::Texture2D) , &t) ,
+        ];
+        change(&mut bindings , &mut o , &mut extra);
+
+        let err = reflection.check_bindings(&bindings).unwrap_err();
+        assert_eq!(err.status , GPUComputingStatus::InvalidArgument);
+        err.message
+    }
+
+    fn storage_texture<'a>(format : GPUTextureFormat , dimension : GPUTextureDimension , data : &'a mut [u32]) -> Binding<'a> {
+        Binding::storage_texture_output(1 , 2 , texture_info(format , dimension) , data)
+    }
+
+    #[test]
+    fn reflects_used_bindings_in_order() {
+        let reflection = reflect(CODE).unwrap();
+
+        assert_eq!(reflection.workgroup_size , [8 , 4 , 1]);
+        assert_eq!(reflection.push_constant_size , 0);
+
+        let places : Vec<(u32 , u32 , GPUBindingKind)> = reflection.bindings.iter()
+            .map(|info| (info.group , info.bind , info.kind))
+            .collect();
+        assert_eq!(places , vec![
+            (0 , 0 , GPUBindingKind::Storage) ,
+            (0 , 1 , GPUBindingKind::Storage) ,
+            (1 , 0 , GPUBindingKind::Uniform) ,
+            (1 , 1 , GPUBindingKind::SampledTexture) ,
+            (1 , 2 , GPUBindingKind::StorageTexture) ,
+        ]);
+
+        let [a , b , p , t , o] = reflection.bindings[..] else {
+            panic!("{:?}" , reflection.bindings);
+        };
+        assert_eq!((a.access , a.element_kind , a.stride , a.array_len , a.min_size) , (GPUDataAccess::ReadWrite , GPUScalarKind::Uint , 4 , 0 , 4));
+        assert_eq!((b.access , b.element_kind , b.element_components , b.stride , b.array_len , b.min_size) , (GPUDataAccess::ReadOnly , GPUScalarKind::Sint , 2 , 8 , 16 , 128));
+        assert_eq!((p.element_kind , p.element_components , p.min_size) , (GPUScalarKind::Float , 4 , 16));
+        assert_eq!((t.element_kind , t.texture_dimension) , (GPUScalarKind::Float , GPUTextureDimension::Texture2D));
+        assert_eq!((o.access , o.texture_format , o.element_kind) , (GPUDataAccess::WriteOnly , GPUTextureFormat::R32Uint , GPUScalarKind::Uint));
+    }
+
+    #[test]
+    fn missing_entry_point_is_reported() {
+        let err = reflect("@compute @workgroup_size(1) fn other() {}").unwrap_err();
+        assert_eq!(err.status , GPUComputingStatus::ShaderCompileError);
+    }
+
+    #[test]
+    fn unsupported_storage_texture_format_is_rejected() {
+        let err = reflect("
+            @group(0) @binding(0) var o : texture_storage_2d<bgra8unorm, write>;
+            @compute @workgroup_size(1) fn main() { textureStore(o , vec2<i32>(0) , vec4<f32>(1.0)); }").unwrap_err();
+
+        assert_eq!(err.status , GPUComputingStatus::UnsupportedFeature);
+        assert!(err.message.contains("Bgra8Unorm") , "{}" , err.message);
+    }
+
+    #[test]
+    fn matching_bindings_pass() {
+        let reflection = reflect(CODE).unwrap();
+        let (mut a , b , p , t , mut o) = ([0u32; 4] , [0i32; 32] , [0f32; 4] , [0f32; 4] , [0u32; 1]);
+
+        let bindings = vec![
+            Binding::read_write(0 , 0 , &mut a) ,
+            Binding::input(0 , 1 , &b) ,
+            Binding::uniform(1 , 0 , &p) ,
+            Binding::sampled_texture(1 , 1 , texture_info(GPUTextureFormat::Rgba32Float , GPUTextureDimension::Texture2D) , &t) ,
+            storage_texture(GPUTextureFormat::R32Uint , GPUTextureDimension::Texture2D , &mut o) ,
+        ];
+        reflection.check_bindings(&bindings).unwrap();
+    }
+
+    #[test]
+    fn missing_binding_is_reported() {
+        let message = check_error(|_ , _ , _| {});
+        assert!(message.contains("group 1 binding 2") && message.contains("not provided") , "{}" , message);
+    }
+
+    #[test]
+    fn duplicate_binding_is_reported() {
+        let message = check_error(|bindings , o , extra| {
+            bindings.push(storage_texture(GPUTextureFormat::R32Uint , GPUTextureDimension::Texture2D , o));
+            bindings.push(Binding::output(0 , 0 , extra));
+        });
+        assert!(message.contains("group 0 binding 0 is provided more than once") , "{}" , message);
+    }
+
+    #[test]
+    fn unused_binding_is_reported() {
+        let message = check_error(|bindings , o , extra| {
+            bindings.push(storage_texture(GPUTextureFormat::R32Uint , GPUTextureDimension::Texture2D , o));
+            bindings.push(Binding::input(2 , 0 , extra));
+        });
+        assert!(message.contains("group 2 binding 0 is not used") , "{}" , message);
+    }
+
+    #[test]
+    fn wrong_kind_is_reported() {
+        let message = check_error(|bindings , o , _| {
+            bindings.push(storage_texture(GPUTextureFormat::R32Uint , GPUTextureDimension::Texture2D , o));
+            bindings[2] = Binding::input(1 , 0 , &[0f32; 4]);
+        });
+        assert!(message.contains("group 1 binding 0 : kernel code declares Uniform but Storage is provided") , "{}" , message);
+    }
+
+    #[test]
+    fn size_below_minimum_is_reported() {
+        let message = check_error(|bindings , o , _| {
+            bindings.push(storage_texture(GPUTextureFormat::R32Uint , GPUTextureDimension::Texture2D , o));
+            bindings[1] = Binding::input(0 , 1 , &[0i32; 16]);
+        });
+        assert!(message.contains("group 0 binding 1 : kernel code needs at least 128 bytes but 64 bytes are provided") , "{}" , message);
+    }
+
+    #[test]
+    fn texture_dimension_mismatch_is_reported() {
+        let message = check_error(|bindings , o , _| {
+            bindings.push(storage_texture(GPUTextureFormat::R32Uint , GPUTextureDimension::Texture3D , o));
+        });
+        assert!(message.contains("group 1 binding 2 : kernel code declares Texture2D texture but Texture3D is provided") , "{}" , message);
+    }
+
+    #[test]
+    fn storage_texture_format_mismatch_is_reported() {
+        let message = check_error(|bindings , o , _| {
+            bindings.push(storage_texture(GPUTextureFormat::Rgba8Unorm , GPUTextureDimension::Texture2D , o));
+        });
+        assert!(message.contains("group 1 binding 2 : kernel code declares R32Uint storage texture but Rgba8Unorm is provided") , "{}" , message);
+    }
+}
@@ -0,0 +1,265 @@
+use crate::error::{GPUComputingError , GPUComputingStatus};
+
+
+#[repr(C)]
+#[derive(Debug , Clone , Copy , PartialEq , Eq , Default)]
+/// since v7.0.0 texel formats which textures of DataBinder
+/// can have , storage textures only support the formats
+/// which wgsl texture_storage_* types accept
+pub enum GPUTextureFormat {
+    /// 4 x 8 bit unsigned normalized , texel is 4 bytes
+    #[default]
+    Rgba8Unorm = 0 ,
+    /// 4 x 8 bit signed normalized , texel is 4 bytes
+    Rgba8Snorm = 1 ,
+    /// 4 x 8 bit unsigned integer , texel is 4 bytes
+    Rgba8Uint = 2 ,
+    /// 4 x 8 bit signed integer , texel is 4 bytes
+    Rgba8Sint = 3 ,
+    /// 4 x 16 bit unsigned integer , texel is 8 bytes
+    Rgba16Uint = 4 ,
+    /// 4 x 16 bit signed integer , texel is 8 bytes
+    Rgba16Sint = 5 ,
+    /// 4 x 16 bit float , texel is 8 bytes
+    Rgba16Float = 6 ,
+    /// 32 bit unsigned integer , texel is 4 bytes
+    R32Uint = 7 ,
+    /// 32 bit signed integer , texel is 4 bytes
+    R32Sint = 8 ,
+    /// 32 bit float , texel is 4 bytes
+    R32Float = 9 ,
+    /// 2 x 32 bit unsigned integer , texel is 8 bytes
+    Rg32Uint = 10 ,
+    /// 2 x 32 bit signed integer , texel is 8 bytes
+    Rg32Sint = 11 ,
+    /// 2 x 32 bit float , texel is 8 bytes
+    Rg32Float = 12 ,
+    /// 4 x 32 bit unsigned integer , texel is 16 bytes
+    Rgba32Uint = 13 ,
+    /// 4 x 32 bit signed integer , texel is 16 bytes
+    Rgba32Sint = 14 ,
+    /// 4 x 32 bit float , texel is 16 bytes
+    Rgba32Float = 15 ,
+    /// 8 bit unsigned normalized , texel is 1 byte ,
+    /// only for sampled textures
+    R8Unorm = 16 ,
+}
+
+impl GPUTextureFormat {
+    pub(crate) fn to_wgpu(self) -> wgpu::TextureFormat {
+        match self {
+            GPUTextureFormat::Rgba8Unorm => wgpu::TextureFormat::Rgba8Unorm ,
+            GPUTextureFormat::Rgba8Snorm => wgpu::TextureFormat::Rgba8Snorm ,
+            GPUTextureFormat::Rgba8Uint => wgpu::TextureFormat::Rgba8Uint ,
+            GPUTextureFormat::Rgba8Sint => wgpu::TextureFormat::Rgba8Sint ,
+            GPUTextureFormat::Rgba16Uint => wgpu::TextureFormat::Rgba16Uint ,
+            GPUTextureFormat::Rgba16Sint => wgpu::TextureFormat::Rgba16Sint ,
+            GPUTextureFormat::Rgba16Float => wgpu::TextureFormat::Rgba16Float ,
+            GPUTextureFormat::R32Uint => wgpu::TextureFormat::R32Uint ,
+            GPUTextureFormat::R32Sint => wgpu::TextureFormat::R32Sint ,
+            GPUTextureFormat::R32Float => wgpu::TextureFormat::R32Float ,
+            GPUTextureFormat::Rg32Uint => wgpu::TextureFormat::Rg32Uint ,
+            GPUTextureFormat::Rg32Sint => wgpu::TextureFormat::Rg32Sint ,
+            GPUTextureFormat::Rg32Float => wgpu::TextureFormat::Rg32Float ,
+            GPUTextureFormat::Rgba32Uint => wgpu::TextureFormat::Rgba32Uint ,
+            GPUTextureFormat::Rgba32Sint => wgpu::TextureFormat::Rgba32Sint ,
+            GPUTextureFormat::Rgba32Float => wgpu::TextureFormat::Rgba32Float ,
+            GPUTextureFormat::R8Unorm => wgpu::TextureFormat::R8Unorm ,
+        }
+    }
+
+    /// size of one texel in bytes
+    pub fn texel_size(self) -> u32 {
+        self.to_wgpu().block_copy_size(None).unwrap_or(0)
+    }
+}
+
+#[repr(C)]
+#[derive(Debug , Clone , Copy , PartialEq , Eq , Default)]
+/// since v7.0.0 dimension of textures of DataBinder
+pub enum GPUTextureDimension {
+    /// texture_2d and texture_storage_2d , depth must be 1
+    #[default]
+    Texture2D = 0 ,
+    /// texture_3d and texture_storage_3d
+    Texture3D = 1 ,
+}
+
+#[repr(C)]
+#[derive(Debug , Clone , Copy , Default)]
+/// since v7.0.0 shape of textures of DataBinder , host data
+/// of the texture must be tightly packed texels , row by row
+/// and slice by slice , so its len must be
+/// width * height * depth * texel size of format .
+/// padding of rows which gpu side needs is handled by the API
+pub struct GPUTextureInfo {
+    pub format : GPUTextureFormat ,
+    pub dimension : GPUTextureDimension ,
+    pub width : u32 ,
+    pub height : u32 ,
+    /// number of slices of 3D textures , it must be 1 for 2D textures
+    pub depth : u32 ,
+}
+
+impl GPUTextureInfo {
+    pub(crate) fn extent(&self) -> wgpu::Extent3d {
+        wgpu::Extent3d {
+            width : self.width ,
+            height : self.height ,
+            depth_or_array_layers : self.depth ,
+        }
+    }
+
+    pub(crate) fn wgpu_dimension(&self) -> wgpu::TextureDimension {
+        match self.dimension {
+            GPUTextureDimension::Texture2D => wgpu::TextureDimension::D2 ,
+            GPUTextureDimension::Texture3D => wgpu::TextureDimension::D3 ,
+        }
+    }
+
+    /// bytes of one row of host data
+    pub(crate) fn row_size(&self) -> u32 {
+        self.width * self.format.texel_size()
+    }
+
+    /// bytes of one row of buffers which textures are copied
+    /// to , gpu side needs COPY_BYTES_PER_ROW_ALIGNMENT
+    pub(crate) fn padded_row_size(&self) -> u32 {
+        self.row_size().next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+    }
+
+    pub(crate) fn rows(&self) -> u32 {
+        self.height * self.depth
+    }
+
+    /// len of host data of the texture
+    pub fn data_len(&self) -> usize {
+        self.row_size() as usize * self.rows() as usize
+    }
+
+    pub(crate) fn check(&self , data_len : usize) -> Result<() , GPUComputingError> {
+        if self.width == 0 || self.height == 0 || self.depth == 0 {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : texture size {}x{}x{} is invalid , width , height and depth must be at least 1" , self.width , self.height , self.depth)));
+        }
+        // bigger textures are rejected by limits of the device anyway
+        let padded_row_size = (self.width as u64 * self.format.texel_size() as u64)
+            .next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64);
+        if padded_row_size > u32::MAX as u64 || padded_row_size * self.height as u64 * self.depth as u64 > isize::MAX as u64 {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : texture size {}x{}x{} is too big" , self.width , self.height , self.depth)));
+        }
+        if self.dimension == GPUTextureDimension::Texture2D && self.depth != 1 {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : depth of 2D textures must be 1 but it is {}" , self.depth)));
+        }
+        if data_len != self.data_len() {
+            return Err(GPUComputingError::new(GPUComputingStatus::InvalidArgument , format!("ERROR : texture {}x{}x{} of {:?} needs {} bytes of data but {} bytes are provided" , self.width , self.height , self.depth , self.format , self.data_len() , data_len)));
+        }
+        Ok(())
+    }
+}
+
+#[repr(C)]
+#[derive(Debug , Clone , Copy , PartialEq , Eq , Default)]
+/// since v7.0.0 filtering of samplers
+pub enum GPUFilterMode {
+    #[default]
+    Nearest = 0 ,
+    /// needs texture format which can be filtered
+    /// (like Rgba8Unorm or Rgba16Float)
+    Linear = 1 ,
+}
+
+#[repr(C)]
+#[derive(Debug , Clone , Copy , PartialEq , Eq , Default)]
+/// since v7.0.0 what samplers return for coordinates
+/// out of 0.0 to 1.0
+pub enum GPUAddressMode {
+    #[default]
+    ClampToEdge = 0 ,
+    Repeat = 1 ,
+    MirrorRepeat = 2 ,
+}
+
+#[repr(C)]
+#[derive(Debug , Clone , Copy , Default)]
+/// since v7.0.0 settings of samplers of DataBinder
+pub struct GPUSamplerInfo {
+    pub filter : GPUFilterMode ,
+    pub address_mode : GPUAddressMode ,
+}
+
+impl GPUSamplerInfo {
+    pub(crate) fn create_sampler(&self , device : &wgpu::Device) -> wgpu::Sampler {
+        let filter = match self.filter {
+            GPUFilterMode::Nearest => wgpu::FilterMode::Nearest ,
+            GPUFilterMode::Linear => wgpu::FilterMode::Linear ,
+        };
+        let address_mode = match self.address_mode {
+            GPUAddressMode::ClampToEdge => wgpu::AddressMode::ClampToEdge ,
+            GPUAddressMode::Repeat => wgpu::AddressMode::Repeat ,
+            GPUAddressMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat ,
+        };
+
+        device.create_sampler(&wgpu::SamplerDescriptor {
+            label: None ,
+            address_mode_u: address_mode ,
+            address_mode_v: address_mode ,
+            address_mode_w: address_mode ,
+            mag_filter: filter ,
+            min_filter: filter ,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(dimension : GPUTextureDimension , width : u32 , height : u32 , depth : u32) -> GPUTextureInfo {
+        GPUTextureInfo {
+            format : GPUTextureFormat::Rgba8Unorm ,
+            dimension ,
+            width ,
+            height ,
+            depth ,
+        }
+    }
+
+    #[test]
+    fn rows_are_padded_to_copy_alignment() {
+        let narrow = info(GPUTextureDimension::Texture2D , 10 , 3 , 1);
+        assert_eq!((narrow.row_size() , narrow.padded_row_size()) , (40 , 256));
+
+        let exact = info(GPUTextureDimension::Texture2D , 64 , 3 , 1);
+        assert_eq!((exact.row_size() , exact.padded_row_size()) , (256 , 256));
+
+        let wide = info(GPUTextureDimension::Texture2D , 65 , 3 , 1);
+        assert_eq!((wide.row_size() , wide.padded_row_size()) , (260 , 512));
+    }
+
+    #[test]
+    fn data_len_is_unpadded() {
+        let narrow = info(GPUTextureDimension::Texture2D , 10 , 3 , 1);
+        assert_eq!(narrow.data_len() , 120);
+        narrow.check(120).unwrap();
+        assert_eq!(narrow.check(3 * 256).unwrap_err().status , GPUComputingStatus::InvalidArgument);
+
+        let exact = info(GPUTextureDimension::Texture2D , 64 , 3 , 1);
+        assert_eq!(exact.data_len() , 768);
+        exact.check(768).unwrap();
+    }
+
+    #[test]
+    fn depth_is_checked() {
+        let volume = info(GPUTextureDimension::Texture3D , 10 , 3 , 4);
+        assert_eq!((volume.rows() , volume.data_len()) , (12 , 480));
+        volume.check(480).unwrap();
+        assert_eq!(volume.check(120).unwrap_err().status , GPUComputingStatus::InvalidArgument);
+
+        let err = info(GPUTextureDimension::Texture2D , 10 , 3 , 4).check(480).unwrap_err();
+        assert!(err.message.contains("depth of 2D textures must be 1") , "{}" , err.message);
+
+        let err = info(GPUTextureDimension::Texture3D , 10 , 3 , 0).check(0).unwrap_err();
+        assert!(err.message.contains("is invalid") , "{}" , err.message);
+    }
+}